@@ -0,0 +1,75 @@
+use rustc_hash::FxHashMap;
+use std::cell::RefCell;
+use wasm_bindgen::{JsCast, JsValue};
+
+thread_local! {
+    /// The constructed stylesheet of each hash.
+    static SHEETS: RefCell<FxHashMap<u64, web_sys::CssStyleSheet>> =
+        RefCell::new(FxHashMap::default());
+}
+
+/// How the css of `<style>` elements gets into the document.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StyleBackend {
+    /// Inject a `<style>` element.
+    #[default]
+    StyleElement,
+    /// Construct a `CSSStyleSheet` and add it to the `adoptedStyleSheets` of the document,
+    /// e.g. when a content security policy forbids inline styles.
+    Constructable,
+}
+
+/// Adds the css as a constructed stylesheet of the document, returns whether it could.
+pub(crate) fn adopt(document: &web_sys::Document, hash: u64, css: &str) -> bool {
+    if SHEETS.with(|sheets| sheets.borrow().contains_key(&hash)) {
+        return true;
+    }
+
+    let Ok(sheet) = web_sys::CssStyleSheet::new() else {
+        return false;
+    };
+
+    if sheet.replace_sync(css).is_err() {
+        return false;
+    }
+
+    let adopted = adopted_style_sheets(document);
+    adopted.push(&sheet);
+
+    if set_adopted_style_sheets(document, &adopted).is_err() {
+        return false;
+    }
+
+    SHEETS.with(|sheets| sheets.borrow_mut().insert(hash, sheet));
+    true
+}
+
+/// Removes the constructed stylesheet of the hash, if there is one.
+pub(crate) fn release(document: &web_sys::Document, hash: u64) {
+    let Some(sheet) = SHEETS.with(|sheets| sheets.borrow_mut().remove(&hash)) else {
+        return;
+    };
+
+    let adopted = adopted_style_sheets(document)
+        .iter()
+        .filter(|adopted| !js_sys::Object::is(adopted, &sheet))
+        .collect::<js_sys::Array>();
+
+    let _ = set_adopted_style_sheets(document, &adopted);
+}
+
+fn adopted_style_sheets(document: &web_sys::Document) -> js_sys::Array {
+    js_sys::Reflect::get(document, &JsValue::from_str("adoptedStyleSheets"))
+        .ok()
+        .and_then(|adopted| adopted.dyn_into::<js_sys::Array>().ok())
+        // copied, as the array of the document may be frozen
+        .map(|adopted| adopted.slice(0, adopted.length()))
+        .unwrap_or_default()
+}
+
+fn set_adopted_style_sheets(
+    document: &web_sys::Document,
+    adopted: &js_sys::Array,
+) -> Result<bool, JsValue> {
+    js_sys::Reflect::set(document, &JsValue::from_str("adoptedStyleSheets"), adopted)
+}
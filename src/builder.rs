@@ -0,0 +1,388 @@
+use crate::OwnedElementMap;
+use std::fmt;
+
+/// Common values of a link's `rel` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rel {
+    Stylesheet,
+    Preload,
+    Icon,
+    Canonical,
+    Manifest,
+    ModulePreload,
+    Preconnect,
+    Prefetch,
+    AppleTouchIcon,
+    Author,
+}
+
+impl Rel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Rel::Stylesheet => "stylesheet",
+            Rel::Preload => "preload",
+            Rel::Icon => "icon",
+            Rel::Canonical => "canonical",
+            Rel::Manifest => "manifest",
+            Rel::ModulePreload => "modulepreload",
+            Rel::Preconnect => "preconnect",
+            Rel::Prefetch => "prefetch",
+            Rel::AppleTouchIcon => "apple-touch-icon",
+            Rel::Author => "author",
+        }
+    }
+}
+
+impl fmt::Display for Rel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The metas and icons of a web app added to the home screen on iOS, see [`HeadBuilder::apple_webapp`].
+#[derive(Debug, Clone, Default)]
+pub struct AppleWebApp {
+    /// The name below the icon, the title of the document without one.
+    pub title: Option<String>,
+    /// `default`, `black` or `black-translucent`.
+    pub status_bar_style: Option<String>,
+    /// The `sizes` and `href` of each `apple-touch-icon`, e.g. `("180x180", "/icon-180.png")`.
+    pub icons: Vec<(String, String)>,
+}
+
+/// Builds head elements without rsx, to be passed to the `elements` prop of Helmet.
+///
+/// ```rust
+/// let elements = HeadBuilder::new()
+///     .link(Rel::Stylesheet, "/style.css")
+///     .meta("description", "Helmet")
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HeadBuilder {
+    elements: Vec<OwnedElementMap>,
+}
+
+impl HeadBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A link of a [`Rel`] or of any other `rel`, e.g. `"author"` for a humans.txt.
+    pub fn link(self, rel: impl fmt::Display, href: impl Into<String>) -> Self {
+        self.element(
+            OwnedElementMap::new("link")
+                .attribute("rel", rel.to_string())
+                .attribute("href", href),
+        )
+    }
+
+    /// A stylesheet the user can choose by its `title`, e.g. a theme.
+    ///
+    /// Alternate stylesheets with different titles are kept side by side.
+    pub fn alternate_stylesheet(self, title: impl Into<String>, href: impl Into<String>) -> Self {
+        self.element(
+            OwnedElementMap::new("link")
+                .attribute("rel", "alternate stylesheet")
+                .attribute("title", title)
+                .attribute("href", href),
+        )
+    }
+
+    /// A light and a dark stylesheet, each only applied for the matching `prefers-color-scheme`.
+    ///
+    /// Both belong to the same Helmet, so they are removed together.
+    pub fn color_scheme_stylesheets(
+        self,
+        light: impl Into<String>,
+        dark: impl Into<String>,
+    ) -> Self {
+        [("light", light.into()), ("dark", dark.into())]
+            .into_iter()
+            .fold(self, |builder, (scheme, href)| {
+                builder.element(
+                    OwnedElementMap::new("link")
+                        .attribute("rel", Rel::Stylesheet.as_str())
+                        .attribute("href", href)
+                        .attribute("media", format!("(prefers-color-scheme: {scheme})")),
+                )
+            })
+    }
+
+    /// Preloads a JavaScript module. Modules at an absolute url are requested with `crossorigin`,
+    /// as they wouldn't be used otherwise if they are from another origin.
+    ///
+    /// Only the url decides, never the current origin, so the server and the client build the
+    /// same element, which is then adopted when hydrating.
+    pub fn modulepreload(self, href: impl Into<String>) -> Self {
+        let href = href.into();
+        let cross_origin = is_absolute(&href);
+
+        let mut element = OwnedElementMap::new("link")
+            .attribute("rel", Rel::ModulePreload.as_str())
+            .attribute("href", href);
+
+        if cross_origin {
+            element = element.attribute("crossorigin", "anonymous");
+        }
+
+        self.element(element)
+    }
+
+    /// Connects to an origin early, once for requests without and once for requests with
+    /// `crossorigin`, e.g. fonts, as browsers don't share these connections.
+    pub fn preconnect(self, href: impl Into<String>) -> Self {
+        let href = href.into();
+
+        self.link(Rel::Preconnect, href.clone()).element(
+            OwnedElementMap::new("link")
+                .attribute("rel", Rel::Preconnect.as_str())
+                .attribute("href", href)
+                .attribute("crossorigin", "anonymous"),
+        )
+    }
+
+    /// The stylesheet of Google Fonts for the families, e.g. `"Roboto:wght@400;700"`,
+    /// with the connections to its origins opened early.
+    ///
+    /// The fonts are swapped in once loaded, so the text is visible before.
+    pub fn google_fonts<S: AsRef<str>>(self, families: &[S]) -> Self {
+        let query = families
+            .iter()
+            .map(|family| format!("family={}", family.as_ref().replace(' ', "+")))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        self.link(Rel::Preconnect, "https://fonts.googleapis.com")
+            // the font files are fetched in cors mode
+            .element(
+                OwnedElementMap::new("link")
+                    .attribute("rel", Rel::Preconnect.as_str())
+                    .attribute("href", "https://fonts.gstatic.com")
+                    .attribute("crossorigin", "anonymous"),
+            )
+            .link(
+                Rel::Stylesheet,
+                format!("https://fonts.googleapis.com/css2?{query}&display=swap"),
+            )
+    }
+
+    /// Preloads a font. Fonts are always fetched in cors mode, so the preload only
+    /// gets used with `crossorigin`, which is set here along with the `type` of the file.
+    pub fn preload_font(self, href: impl Into<String>) -> Self {
+        self.element(font_preload(href.into()))
+    }
+
+    /// Preloads the image the browser picks from the candidates, like an `<img srcset sizes>` would.
+    ///
+    /// ```rust
+    /// HeadBuilder::new().preload_responsive_image("hero-480.jpg 480w, hero-960.jpg 960w", "100vw");
+    /// ```
+    pub fn preload_responsive_image(
+        self,
+        imagesrcset: impl Into<String>,
+        imagesizes: impl Into<String>,
+    ) -> Self {
+        self.element(
+            OwnedElementMap::new("link")
+                .attribute("rel", Rel::Preload.as_str())
+                .attribute("as", "image")
+                .attribute("imagesrcset", imagesrcset)
+                .attribute("imagesizes", imagesizes),
+        )
+    }
+
+    /// A `theme-color` for the light and one for the dark `prefers-color-scheme`.
+    ///
+    /// Each is keyed by its media, so changing a color updates its meta in place.
+    pub fn theme_color(self, light: impl Into<String>, dark: impl Into<String>) -> Self {
+        [("light", light.into()), ("dark", dark.into())]
+            .into_iter()
+            .fold(self, |builder, (scheme, color)| {
+                let media = format!("(prefers-color-scheme: {scheme})");
+
+                builder.element(
+                    OwnedElementMap::new("meta")
+                        .attribute("name", "theme-color")
+                        .attribute("content", color)
+                        .attribute(
+                            "data-helmet-key",
+                            format!("meta[name=theme-color][media={media}]"),
+                        )
+                        .attribute("media", media),
+                )
+            })
+    }
+
+    /// Every meta and icon iOS reads for a web app on the home screen, so they are injected
+    /// and removed together.
+    ///
+    /// ```rust
+    /// HeadBuilder::new().apple_webapp(AppleWebApp {
+    ///     title: Some("Helmet".into()),
+    ///     icons: vec![("180x180".into(), "/icon-180.png".into())],
+    ///     ..Default::default()
+    /// });
+    /// ```
+    pub fn apple_webapp(self, config: AppleWebApp) -> Self {
+        let AppleWebApp {
+            title,
+            status_bar_style,
+            icons,
+        } = config;
+
+        let mut builder = self.meta("apple-mobile-web-app-capable", "yes");
+
+        if let Some(title) = title {
+            builder = builder.meta("apple-mobile-web-app-title", title);
+        }
+
+        if let Some(status_bar_style) = status_bar_style {
+            builder = builder.meta("apple-mobile-web-app-status-bar-style", status_bar_style);
+        }
+
+        icons.into_iter().fold(builder, |builder, (sizes, href)| {
+            builder.element(
+                OwnedElementMap::new("link")
+                    .attribute("rel", Rel::AppleTouchIcon.as_str())
+                    .attribute("sizes", sizes)
+                    .attribute("href", href),
+            )
+        })
+    }
+
+    /// The rules of the Speculation Rules API, e.g. to prerender the next page.
+    ///
+    /// There is one set of rules in the head, so they replace the ones of another Helmet.
+    ///
+    /// ```rust
+    /// HeadBuilder::new().speculation_rules(&serde_json::json!({
+    ///     "prerender": [{ "source": "list", "urls": ["/next"] }]
+    /// }));
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn speculation_rules(self, rules: &serde_json::Value) -> Self {
+        // `<\/` is the same in a json string, but can't close the script
+        let rules = rules.to_string().replace("</", "<\\/");
+
+        self.element(
+            OwnedElementMap::new("script")
+                .attribute("type", "speculationrules")
+                .inner_html(rules),
+        )
+    }
+
+    pub fn meta(self, name: impl Into<String>, content: impl Into<String>) -> Self {
+        self.element(
+            OwnedElementMap::new("meta")
+                .attribute("name", name)
+                .attribute("content", content),
+        )
+    }
+
+    pub fn element(mut self, element: OwnedElementMap) -> Self {
+        self.elements.push(element);
+        self
+    }
+
+    pub fn build(self) -> Vec<OwnedElementMap> {
+        self.elements
+    }
+}
+
+/// Whether the url may point to another origin, i.e. has an origin of its own.
+///
+/// `crossorigin="anonymous"` only sends credentials to the same origin, as a module does
+/// without it, so an absolute url to the same origin loses nothing.
+fn is_absolute(href: &str) -> bool {
+    href.starts_with("//") || href.contains("://")
+}
+
+pub(crate) fn font_preload(href: String) -> OwnedElementMap {
+    let extension = href
+        .split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit_once('.'))
+        .map(|(_, extension)| extension.to_ascii_lowercase());
+
+    let mime = match extension.as_deref() {
+        Some("woff2") => Some("font/woff2"),
+        Some("woff") => Some("font/woff"),
+        Some("ttf") => Some("font/ttf"),
+        Some("otf") => Some("font/otf"),
+        _ => None,
+    };
+
+    let mut element = OwnedElementMap::new("link")
+        .attribute("rel", Rel::Preload.as_str())
+        .attribute("href", href)
+        .attribute("as", "font");
+
+    if let Some(mime) = mime {
+        element = element.attribute("type", mime);
+    }
+
+    element.attribute("crossorigin", "anonymous")
+}
+
+/// Builds a `<meta>` element, refusing to compile unless it has `name` and `content`,
+/// `property` and `content`, or `charset`, in either order.
+///
+/// ```rust
+/// let elements = HeadBuilder::new()
+///     .element(meta!(charset = "utf-8"))
+///     .element(meta!(name = "description", content = "Helmet"))
+///     .element(meta!(property = "og:title", content = "Helmet"))
+///     .element(meta!(content = "summary", name = "twitter:card"))
+///     .build();
+/// ```
+///
+/// ```compile_fail
+/// meta!(name = "description");
+/// ```
+#[macro_export]
+macro_rules! meta {
+    (name = $name:expr, content = $content:expr $(,)?) => {
+        $crate::OwnedElementMap::new("meta")
+            .attribute("name", $name)
+            .attribute("content", $content)
+    };
+    (property = $property:expr, content = $content:expr $(,)?) => {
+        $crate::OwnedElementMap::new("meta")
+            .attribute("property", $property)
+            .attribute("content", $content)
+    };
+    (content = $content:expr, name = $name:expr $(,)?) => {
+        $crate::meta!(name = $name, content = $content)
+    };
+    (content = $content:expr, property = $property:expr $(,)?) => {
+        $crate::meta!(property = $property, content = $content)
+    };
+    (charset = $charset:expr $(,)?) => {
+        $crate::OwnedElementMap::new("meta").attribute("charset", $charset)
+    };
+    ($($rest:tt)*) => {
+        compile_error!("meta! needs `name` and `content`, `property` and `content`, or `charset`")
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modulepreload_decides_crossorigin_by_the_url_alone() {
+        let elements = HeadBuilder::new()
+            .modulepreload("https://own.site/app.js")
+            .modulepreload("//cdn.example.com/lib.js")
+            .modulepreload("/app.js")
+            .build();
+
+        let cross_origins = elements
+            .iter()
+            .map(|element| element.as_element_map().attribute("crossorigin").is_some())
+            .collect::<Vec<_>>();
+
+        assert_eq!(cross_origins, [true, true, false]);
+    }
+}
@@ -0,0 +1,85 @@
+use rustc_hash::FxHashMap;
+use std::{cell::RefCell, future::Future, pin::Pin};
+
+type RemoveCallback = Box<dyn FnMut(&web_sys::Element)>;
+pub(crate) type Teardown = Pin<Box<dyn Future<Output = ()>>>;
+type TeardownCallback = Box<dyn FnMut(&web_sys::Element) -> Teardown>;
+
+thread_local! {
+    static ON_REMOVE: RefCell<FxHashMap<String, RemoveCallback>> = RefCell::new(FxHashMap::default());
+    static ON_REMOVE_ASYNC: RefCell<FxHashMap<String, TeardownCallback>> = RefCell::new(FxHashMap::default());
+}
+
+/// Registers a callback which runs right before an element with the given
+/// `data-helmet-key` is removed from the head, e.g. to tear down a widget its script created.
+///
+/// ```rust
+/// on_remove("widget", |_| teardown_widget());
+///
+/// rsx! {
+///     Helmet {
+///         script { "data-helmet-key": "widget", src: "https://example.com/widget.js" }
+///     }
+/// }
+/// ```
+///
+/// Registering another callback for the same key replaces the previous one.
+pub fn on_remove(key: impl Into<String>, callback: impl FnMut(&web_sys::Element) + 'static) {
+    ON_REMOVE.with(|on_remove| {
+        on_remove
+            .borrow_mut()
+            .insert(key.into(), Box::new(callback));
+    });
+}
+
+/// Like [`on_remove`], but the element stays in the head until the returned future resolves.
+///
+/// ```rust
+/// on_remove_async("widget", |_| async { widget_shutdown().await });
+/// ```
+///
+/// It runs after a callback registered with [`on_remove`] for the same key.
+pub fn on_remove_async<F>(
+    key: impl Into<String>,
+    mut callback: impl FnMut(&web_sys::Element) -> F + 'static,
+) where
+    F: Future<Output = ()> + 'static,
+{
+    ON_REMOVE_ASYNC.with(|on_remove_async| {
+        on_remove_async.borrow_mut().insert(
+            key.into(),
+            Box::new(move |element| Box::pin(callback(element))),
+        );
+    });
+}
+
+pub(crate) fn run_on_remove(element: &web_sys::Element) {
+    let Some(key) = element.get_attribute("data-helmet-key") else {
+        return;
+    };
+
+    // taken out while running, so the callback may register callbacks itself
+    let Some(mut callback) = ON_REMOVE.with(|on_remove| on_remove.borrow_mut().remove(&key)) else {
+        return;
+    };
+
+    callback(element);
+
+    ON_REMOVE.with(|on_remove| {
+        on_remove.borrow_mut().entry(key).or_insert(callback);
+    });
+}
+
+pub(crate) fn run_on_remove_async(element: &web_sys::Element) -> Option<Teardown> {
+    let key = element.get_attribute("data-helmet-key")?;
+    let mut callback =
+        ON_REMOVE_ASYNC.with(|on_remove_async| on_remove_async.borrow_mut().remove(&key))?;
+
+    let teardown = callback(element);
+
+    ON_REMOVE_ASYNC.with(|on_remove_async| {
+        on_remove_async.borrow_mut().entry(key).or_insert(callback);
+    });
+
+    Some(teardown)
+}
@@ -0,0 +1,130 @@
+use crate::{document, lock_init_cache, remove_elements};
+use dioxus::prelude::ScopeId;
+use lazy_static::lazy_static;
+use rustc_hash::FxHashMap;
+use std::{cell::RefCell, sync::Mutex, time::Duration};
+#[cfg(not(feature = "ssr-only"))]
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+lazy_static! {
+    static ref CLEANUP_DELAY: Mutex<Option<Duration>> = Mutex::new(None);
+}
+
+thread_local! {
+    /// Elements waiting for their removal, with the generation of the timeout which removes them.
+    static LINGERING: RefCell<FxHashMap<u64, (web_sys::Document, u32)>> =
+        RefCell::new(FxHashMap::default());
+}
+
+/// Keeps the elements of a dropped Helmet for the given delay before removing them.
+///
+/// If another Helmet injects the same element within that time, it takes over the
+/// existing one, so navigating between pages sharing e.g. a canonical link doesn't touch the DOM.
+pub fn set_cleanup_delay(delay: Option<Duration>) {
+    *CLEANUP_DELAY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = delay;
+}
+
+#[cfg(not(feature = "ssr-only"))]
+fn cleanup_delay() -> Option<Duration> {
+    *CLEANUP_DELAY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Removes every element a Helmet in the given scope injected, e.g. to force the cleanup
+/// of a scope which is known to be gone. Those elements are marked with `data-helmet-scope`.
+///
+/// ```rust
+/// remove_scope(cx.scope_id());
+/// ```
+pub fn remove_scope(scope: ScopeId) {
+    let Some(document) = document() else {
+        return;
+    };
+    let Ok(elements) = document.query_selector_all(&format!("[data-helmet-scope='{}']", scope.0))
+    else {
+        return;
+    };
+
+    let hashes = (0..elements.length())
+        .filter_map(|index| elements.item(index))
+        .filter_map(|node| {
+            node.dyn_into::<web_sys::Element>()
+                .ok()?
+                .get_attribute("data-helmet-id")?
+                .parse::<u64>()
+                .ok()
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(mut init_cache) = lock_init_cache() {
+        init_cache.retain(|hash| !hashes.contains(hash));
+    }
+
+    hashes.iter().for_each(|hash| {
+        revive(*hash);
+        remove_elements(&document, *hash);
+    });
+}
+
+#[cfg(not(feature = "ssr-only"))]
+/// Schedules the removal if a cleanup delay is set.
+pub(crate) fn defer(document: &web_sys::Document, hash: u64) -> bool {
+    let Some(delay) = cleanup_delay() else {
+        return false;
+    };
+
+    defer_for(document, hash, delay)
+}
+
+#[cfg(not(feature = "ssr-only"))]
+/// Schedules the removal after the given delay, regardless of the cleanup delay.
+pub(crate) fn defer_for(document: &web_sys::Document, hash: u64, delay: Duration) -> bool {
+    let Some(window) = web_sys::window() else {
+        return false;
+    };
+
+    let generation = LINGERING.with(|lingering| {
+        let mut lingering = lingering.borrow_mut();
+        let generation = lingering
+            .get(&hash)
+            .map_or(0, |(_, generation)| generation + 1);
+        lingering.insert(hash, (document.clone(), generation));
+        generation
+    });
+
+    let callback = Closure::once_into_js(move || expire(hash, generation));
+
+    window
+        .set_timeout_with_callback_and_timeout_and_arguments_0(
+            callback.unchecked_ref(),
+            delay.as_millis().try_into().unwrap_or(i32::MAX),
+        )
+        .is_ok()
+}
+
+/// Takes over a lingering element, returns whether there was one.
+pub(crate) fn revive(hash: u64) -> bool {
+    LINGERING.with(|lingering| lingering.borrow_mut().remove(&hash).is_some())
+}
+
+#[cfg(not(feature = "ssr-only"))]
+fn expire(hash: u64, generation: u32) {
+    let document = LINGERING.with(|lingering| {
+        let mut lingering = lingering.borrow_mut();
+
+        // revived or scheduled again in the meantime
+        if lingering.get(&hash).map(|(_, g)| *g) != Some(generation) {
+            return None;
+        }
+
+        lingering.remove(&hash).map(|(document, _)| document)
+    });
+
+    if let Some(document) = document {
+        remove_elements(&document, hash);
+    }
+}
@@ -0,0 +1,60 @@
+use rustc_hash::{FxHashMap, FxHasher};
+use std::{
+    cell::RefCell,
+    hash::{Hash, Hasher},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static INSTANCE_DOCUMENTS: RefCell<FxHashMap<usize, DocumentHandle>> =
+        RefCell::new(FxHashMap::default());
+}
+
+/// The document Helmets below this context place their elements in,
+/// e.g. the document of another window in a multi-window app.
+///
+/// ```rust
+/// cx.provide_context(DocumentHandle::new(other_window.document().unwrap()));
+/// ```
+///
+/// Without it, Helmet uses the document of the current window.
+#[derive(Debug, Clone)]
+pub struct DocumentHandle {
+    id: u64,
+    document: web_sys::Document,
+}
+
+impl DocumentHandle {
+    pub fn new(document: web_sys::Document) -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            document,
+        }
+    }
+
+    pub fn document(&self) -> &web_sys::Document {
+        &self.document
+    }
+
+    /// Keeps identical elements in different documents apart.
+    pub(crate) fn seed(&self, seed: i64) -> i64 {
+        let mut hasher = FxHasher::default();
+        seed.hash(&mut hasher);
+        self.id.hash(&mut hasher);
+        hasher.finish() as i64
+    }
+}
+
+/// Remembers the document of a Helmet instance, so its cleanup targets the same one.
+pub(crate) fn remember(instance: usize, handle: DocumentHandle) {
+    INSTANCE_DOCUMENTS.with(|documents| {
+        documents.borrow_mut().insert(instance, handle);
+    });
+}
+
+#[cfg(not(feature = "ssr-only"))]
+pub(crate) fn forget(instance: usize) -> Option<DocumentHandle> {
+    INSTANCE_DOCUMENTS.with(|documents| documents.borrow_mut().remove(&instance))
+}
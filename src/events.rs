@@ -0,0 +1,37 @@
+use lazy_static::lazy_static;
+use std::sync::Mutex;
+
+lazy_static! {
+    static ref EVENTS: Mutex<Vec<HelmetEvent>> = Mutex::new(Vec::new());
+}
+
+/// An element Helmet placed in or removed from the head, with its hash and tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HelmetEvent {
+    Inject(u64, String),
+    Remove(u64, String),
+}
+
+/// Takes every event recorded since the last call, oldest first.
+///
+/// ```rust
+/// // render, drop...
+/// assert_eq!(
+///     take_events(),
+///     [HelmetEvent::Inject(h1, "meta".into()), HelmetEvent::Remove(h1, "meta".into())]
+/// );
+/// ```
+pub fn take_events() -> Vec<HelmetEvent> {
+    std::mem::take(
+        &mut *EVENTS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()),
+    )
+}
+
+pub(crate) fn record(event: HelmetEvent) {
+    EVENTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .push(event);
+}
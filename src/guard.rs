@@ -0,0 +1,91 @@
+use lazy_static::lazy_static;
+use rustc_hash::FxHashMap;
+use std::{cell::RefCell, sync::Mutex, time::Duration};
+
+lazy_static! {
+    static ref INJECTION_LIMIT: Mutex<Option<InjectionLimit>> = Mutex::new(None);
+}
+
+thread_local! {
+    /// The start of the current window of each Helmet scope and its injections within it.
+    ///
+    /// Keyed by scope, as the props of a Helmet are created again on every render of the parent.
+    static INJECTIONS: RefCell<FxHashMap<usize, (f64, usize)>> =
+        RefCell::new(FxHashMap::default());
+}
+
+/// How many elements a single Helmet may inject within a window, see [`set_injection_limit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InjectionLimit {
+    pub max: usize,
+    pub window: Duration,
+    /// Stop injecting until the window is over, instead of only warning.
+    pub stop: bool,
+}
+
+/// Warns once a Helmet injects more than `max` elements within `window`, e.g. because it renders
+/// changing content on every frame, instead of silently filling the head.
+///
+/// ```rust
+/// set_injection_limit(Some(InjectionLimit {
+///     max: 100,
+///     window: Duration::from_secs(1),
+///     stop: true,
+/// }));
+/// ```
+pub fn set_injection_limit(limit: Option<InjectionLimit>) {
+    *INJECTION_LIMIT
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = limit;
+}
+
+fn injection_limit() -> Option<InjectionLimit> {
+    *INJECTION_LIMIT
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Counts an injection of the scope, returns whether it may go on.
+pub(crate) fn allow(scope: usize, tag: &str) -> bool {
+    let Some(limit) = injection_limit() else {
+        return true;
+    };
+
+    let now = js_sys::Date::now();
+    let window = limit.window.as_secs_f64() * 1000.0;
+
+    let count = INJECTIONS.with(|injections| {
+        let mut injections = injections.borrow_mut();
+        let (start, count) = injections.entry(scope).or_insert((now, 0));
+
+        if now - *start > window {
+            *start = now;
+            *count = 0;
+        }
+
+        *count += 1;
+        *count
+    });
+
+    if count <= limit.max {
+        return true;
+    }
+
+    // once per window
+    if count == limit.max + 1 {
+        log::warn!(
+            "dioxus-helmet: a Helmet injected more than {} elements within {:?}, the last one a <{tag}>. \
+             Does it render changing content on every render?",
+            limit.max,
+            limit.window
+        );
+    }
+
+    !limit.stop
+}
+
+pub(crate) fn forget(scope: usize) {
+    INJECTIONS.with(|injections| {
+        injections.borrow_mut().remove(&scope);
+    });
+}
@@ -0,0 +1,119 @@
+use crate::{
+    document, inject::NEXT_GUARD, inject_owned, lock_init_cache, owners, remove_elements,
+    set_title, OwnedElementMap, Placement,
+};
+use dioxus::prelude::*;
+use std::{cell::RefCell, fmt::Display, rc::Rc, sync::atomic::Ordering};
+
+/// The value the elements of a [`use_head`] were built from, and their hashes.
+struct HeadState<T> {
+    instance: usize,
+    value: Option<T>,
+    hashes: Vec<u64>,
+}
+
+/// Injects the elements built from a value, e.g. of a `use_state`, building them again whenever
+/// the value changes and removing them when the component is dropped.
+///
+/// Unlike Helmet, it can be used together with states in the same component. Elements with a
+/// `data-helmet-key` are updated in place, and elements shared with a Helmet stay until both
+/// of them are gone.
+///
+/// ```rust
+/// let theme = use_state(cx, || "#fff".to_owned());
+/// use_head(cx, theme.get(), |theme| {
+///     HeadBuilder::new()
+///         .element(meta!(name = "theme-color", content = theme).attribute("data-helmet-key", "theme"))
+///         .build()
+/// });
+/// ```
+pub fn use_head<T: PartialEq + Clone + 'static>(
+    cx: &ScopeState,
+    value: &T,
+    build: impl FnOnce(&T) -> Vec<OwnedElementMap>,
+) {
+    let state = cx
+        .use_hook(|| {
+            Rc::new(RefCell::new(HeadState::<T> {
+                instance: NEXT_GUARD.fetch_add(1, Ordering::Relaxed),
+                value: None,
+                hashes: Vec::new(),
+            }))
+        })
+        .clone();
+
+    use_on_destroy(cx, {
+        let state = state.clone();
+        move || {
+            let state = state.borrow();
+            remove(state.instance, &state.hashes)
+        }
+    });
+
+    let mut state = state.borrow_mut();
+
+    if state.value.as_ref() == Some(value) {
+        return;
+    }
+
+    let hashes = build(value)
+        .iter()
+        .filter_map(|element| inject_owned(0, element, Placement::default()))
+        .map(|(hash, _)| {
+            owners::claim(hash, state.instance);
+            hash
+        })
+        .collect::<Vec<_>>();
+
+    let stale = state
+        .hashes
+        .iter()
+        .copied()
+        .filter(|hash| !hashes.contains(hash))
+        .collect::<Vec<_>>();
+    remove(state.instance, &stale);
+
+    state.value = Some(value.clone());
+    state.hashes = hashes;
+}
+
+/// Sets the title of the document to a value, e.g. of a `use_state`, whenever it changes.
+///
+/// The `<title>` is updated in place, so the tab doesn't flicker.
+///
+/// ```rust
+/// let unread = use_state(cx, || 0);
+/// use_title(cx, format!("Inbox ({unread})"));
+/// ```
+pub fn use_title(cx: &ScopeState, title: impl Display) {
+    let last = cx.use_hook(|| RefCell::new(None::<String>));
+    let title = title.to_string();
+
+    if last.borrow().as_deref() == Some(title.as_str()) {
+        return;
+    }
+
+    set_title(&title);
+    *last.borrow_mut() = Some(title);
+}
+
+/// Removes the elements no other Helmet, guard or hook owns as well.
+fn remove(instance: usize, hashes: &[u64]) {
+    let hashes = hashes
+        .iter()
+        .copied()
+        .filter(|&hash| owners::release(hash, instance))
+        .collect::<Vec<_>>();
+
+    let Some(document) = document() else {
+        return;
+    };
+
+    if let Some(mut init_cache) = lock_init_cache() {
+        init_cache.retain(|hash| !hashes.contains(hash));
+    }
+
+    hashes
+        .iter()
+        .for_each(|&hash| remove_elements(&document, hash));
+}
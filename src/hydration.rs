@@ -0,0 +1,63 @@
+use crate::PendingInsertion;
+use std::cell::RefCell;
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// Set on `<html>` by the server while the head it rendered waits for hydration.
+const HYDRATING: &str = "data-helmet-hydrating";
+
+thread_local! {
+    static PENDING: RefCell<Vec<PendingInsertion>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Queues the insertion while the document is still hydrating, returns whether it did.
+pub(crate) fn defer(
+    document: &web_sys::Document,
+    insertion: impl FnOnce() -> PendingInsertion,
+) -> bool {
+    let Some(root) = document.document_element() else {
+        return false;
+    };
+
+    if !root.has_attribute(HYDRATING) {
+        return false;
+    }
+
+    let listen = PENDING.with(|pending| {
+        let mut pending = pending.borrow_mut();
+        pending.push(insertion());
+        pending.len() == 1
+    });
+
+    if listen {
+        let callback = Closure::once_into_js(hydrated);
+        let _ =
+            document.add_event_listener_with_callback("helmet-hydrated", callback.unchecked_ref());
+    }
+
+    true
+}
+
+#[cfg(not(feature = "ssr-only"))]
+/// Drops the queued insertion, e.g. when its Helmet went away during hydration.
+pub(crate) fn cancel(hash: u64) {
+    PENDING.with(|pending| {
+        pending
+            .borrow_mut()
+            .retain(|insertion| insertion.hash != hash)
+    });
+}
+
+/// Ends the hydration window and injects the elements which waited for it, taking over
+/// the ones the server already rendered instead of injecting them a second time.
+///
+/// Let the server render `<html data-helmet-hydrating>` to hold back Helmet until then.
+/// Dispatching a `helmet-hydrated` event on the document does the same.
+pub fn hydrated() {
+    let Some(root) = crate::document().and_then(|document| document.document_element()) else {
+        return;
+    };
+
+    let _ = root.remove_attribute(HYDRATING);
+
+    PendingInsertion::apply_queued(&PENDING);
+}
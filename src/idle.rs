@@ -0,0 +1,50 @@
+use crate::PendingInsertion;
+use std::cell::RefCell;
+use wasm_bindgen::{closure::Closure, JsCast};
+
+thread_local! {
+    static PENDING: RefCell<Vec<PendingInsertion>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// Queues the insertion until the browser is idle.
+pub(crate) fn defer(insertion: PendingInsertion) {
+    let schedule = PENDING.with(|pending| {
+        let mut pending = pending.borrow_mut();
+        pending.push(insertion);
+        pending.len() == 1
+    });
+
+    if schedule {
+        schedule_flush();
+    }
+}
+
+#[cfg(not(feature = "ssr-only"))]
+/// Drops the queued insertion, e.g. when its Helmet went away before the browser was idle.
+pub(crate) fn cancel(hash: u64) {
+    PENDING.with(|pending| {
+        pending
+            .borrow_mut()
+            .retain(|insertion| insertion.hash != hash)
+    });
+}
+
+/// Inserts every queued element.
+pub(crate) fn flush() {
+    PendingInsertion::apply_queued(&PENDING);
+}
+
+fn schedule_flush() {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+
+    let callback = Closure::once_into_js(flush);
+    let callback = callback.unchecked_ref();
+
+    // not every browser supports requestIdleCallback
+    if window.request_idle_callback(callback).is_err() {
+        let _ = window.set_timeout_with_callback(callback);
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{
+    cleanup, document, element_hash, inject_owned, insert_element, inspect, lock_init_cache,
+    owners, remove_elements, HeadBuilder, OwnedElementMap, Placement,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Identifies the guards and [`use_head`](crate::use_head)s as owners, apart from the Helmets
+/// which are identified by their address.
+pub(crate) static NEXT_GUARD: AtomicUsize = AtomicUsize::new(1);
+
+/// Keeps the elements of [`inject`] in the head until it is dropped.
+#[must_use = "the elements are removed as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct HeadGuard {
+    instance: usize,
+    hashes: Vec<u64>,
+}
+
+/// Injects elements without a Helmet, e.g. from a router guard, until the returned guard is dropped.
+///
+/// ```rust
+/// let _guard = inject(HeadBuilder::new().meta("robots", "noindex").build());
+/// ```
+///
+/// Elements shared with a Helmet stay until both of them are gone.
+pub fn inject(elements: impl IntoIterator<Item = OwnedElementMap>) -> HeadGuard {
+    let instance = NEXT_GUARD.fetch_add(1, Ordering::Relaxed);
+
+    let hashes = elements
+        .into_iter()
+        .filter_map(|element| inject_owned(0, &element, Placement::default()))
+        .map(|(hash, _)| {
+            owners::claim(hash, instance);
+            hash
+        })
+        .collect();
+
+    HeadGuard { instance, hashes }
+}
+
+/// Injects an element with content which is only computed if the element isn't injected yet,
+/// e.g. a large JSON-LD, until the returned guard is dropped.
+///
+/// ```rust
+/// let _guard = inject_with(
+///     OwnedElementMap::new("script").attribute("type", "application/ld+json"),
+///     || serde_json::to_string(&product).unwrap(),
+/// );
+/// ```
+///
+/// The element without its content tells it apart, so content which changes
+/// needs a changing attribute as well, e.g. a `data-version`.
+pub fn inject_with(element: OwnedElementMap, content: impl FnOnce() -> String) -> HeadGuard {
+    let instance = NEXT_GUARD.fetch_add(1, Ordering::Relaxed);
+
+    let hashes = inject_lazily(element, content)
+        .inspect(|&hash| {
+            owners::claim(hash, instance);
+        })
+        .into_iter()
+        .collect();
+
+    HeadGuard { instance, hashes }
+}
+
+fn inject_lazily(element: OwnedElementMap, content: impl FnOnce() -> String) -> Option<u64> {
+    let document = document()?;
+    let head = document.head()?;
+    let hash = element_hash(0, &element.as_element_map());
+
+    let mut init_cache = lock_init_cache()?;
+
+    if !init_cache.contains(&hash) {
+        init_cache.push(hash);
+        inspect::record_seed(hash, 0);
+
+        // taken over from a dropped Helmet before its cleanup delay removes it
+        if cleanup::revive(hash) {
+            return Some(hash);
+        }
+
+        let element = element.inner_html(content());
+        insert_element(
+            &document,
+            &head,
+            &mut init_cache,
+            &element.as_element_map(),
+            hash,
+            Placement::default(),
+        );
+    }
+
+    Some(hash)
+}
+
+/// Injects the links of [`HeadBuilder::google_fonts`], which are removed together once the
+/// returned guard is dropped.
+///
+/// ```rust
+/// let _fonts = inject_google_fonts(&["Roboto:wght@400;700", "Fira Code"]);
+/// ```
+pub fn inject_google_fonts<S: AsRef<str>>(families: &[S]) -> HeadGuard {
+    inject(HeadBuilder::new().google_fonts(families).build())
+}
+
+impl Drop for HeadGuard {
+    fn drop(&mut self) {
+        let Some(document) = document() else {
+            return;
+        };
+
+        let hashes = self
+            .hashes
+            .iter()
+            .copied()
+            .filter(|&hash| owners::release(hash, self.instance))
+            .collect::<Vec<_>>();
+
+        if let Some(mut init_cache) = lock_init_cache() {
+            init_cache.retain(|hash| !hashes.contains(hash));
+        }
+
+        hashes
+            .iter()
+            .for_each(|&hash| remove_elements(&document, hash));
+    }
+}
+
+/// Injects a `<meta name content>` without a Helmet, e.g. from an event handler.
+///
+/// ```rust
+/// inject_meta("theme-color", "#fff");
+/// ```
+///
+/// Injecting the same name again replaces its content.
+pub fn inject_meta(name: &str, content: &str) {
+    let element = OwnedElementMap::new("meta")
+        .attribute("name", name)
+        .attribute("content", content)
+        .attribute("data-helmet-key", meta_key(name));
+
+    inject_owned(0, &element, Placement::default());
+}
+
+/// Removes a meta injected with [`inject_meta`].
+pub fn remove_meta(name: &str) {
+    let Some(document) = document() else {
+        return;
+    };
+
+    let hash = document
+        .query_selector(&format!("[data-helmet-key='{}']", meta_key(name)))
+        .ok()
+        .flatten()
+        .and_then(|element| element.get_attribute("data-helmet-id"))
+        .and_then(|id| id.parse::<u64>().ok());
+
+    let Some(hash) = hash else {
+        return;
+    };
+
+    if let Some(mut init_cache) = lock_init_cache() {
+        init_cache.retain(|&c| c != hash);
+    }
+
+    remove_elements(&document, hash);
+}
+
+fn meta_key(name: &str) -> String {
+    format!("meta[name={name}]")
+}
@@ -0,0 +1,115 @@
+use crate::{document, element_map_to_html, OwnedElementMap};
+use rustc_hash::FxHashMap;
+use std::cell::RefCell;
+use wasm_bindgen::JsCast;
+
+/// The attributes Helmet marks the elements with, unlike a `data-helmet-key` or
+/// `data-helmet-critical` which come from the element itself.
+const MARKERS: &[&str] = &[
+    "data-helmet-id",
+    "data-helmet-hash",
+    "data-helmet-version",
+    "data-helmet-scope",
+    "data-helmet-loaded",
+    "data-helmet-failed",
+];
+
+thread_local! {
+    /// The seed each element was injected with.
+    static SEEDS: RefCell<FxHashMap<u64, i64>> = RefCell::new(FxHashMap::default());
+}
+
+/// An element placed by Helmet, see [`head_elements`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HeadElementInfo {
+    /// The `data-helmet-id` of the element.
+    pub hash: u64,
+    /// The `data-helmet-key` of the element, if it replaces others with the same key.
+    pub key: Option<String>,
+    /// The seed of the Helmet which injected it, none if it was injected in another way,
+    /// e.g. adopted during hydration.
+    pub seed: Option<i64>,
+    pub element: OwnedElementMap,
+}
+
+/// Serializes every element placed by Helmet, in the order of the document.
+///
+/// Handy to compare against an expected string in tests, as the markers of Helmet, e.g. its
+/// `data-helmet-version`, are left out.
+pub fn head_snapshot() -> String {
+    helmet_elements()
+        .map(|element| element_map_to_html(&element_map(&element)))
+        .collect()
+}
+
+/// Every element placed by Helmet, in the order of the document.
+///
+/// With the `serde` feature, they can be saved, e.g. to the session storage, and restored
+/// on reload by passing them to the `elements` prop of a Helmet.
+pub fn injected_elements() -> Vec<OwnedElementMap> {
+    helmet_elements()
+        .map(|element| element_map(&element))
+        .collect()
+}
+
+/// Describes every element placed by Helmet, in the order of the document, e.g. for a devtools panel.
+pub fn head_elements() -> Vec<HeadElementInfo> {
+    helmet_elements()
+        .filter_map(|element| {
+            let hash = element.get_attribute("data-helmet-id")?.parse().ok()?;
+
+            Some(HeadElementInfo {
+                hash,
+                key: element.get_attribute("data-helmet-key"),
+                seed: SEEDS.with(|seeds| seeds.borrow().get(&hash).copied()),
+                element: element_map(&element),
+            })
+        })
+        .collect()
+}
+
+/// Every element placed by Helmet, in the order of the document, none without a document.
+fn helmet_elements() -> impl Iterator<Item = web_sys::Element> {
+    let elements =
+        document().and_then(|document| document.query_selector_all("[data-helmet-id]").ok());
+
+    elements.into_iter().flat_map(|elements| {
+        (0..elements.length())
+            .filter_map(move |index| elements.get(index))
+            .filter_map(|node| node.dyn_into::<web_sys::Element>().ok())
+    })
+}
+
+pub(crate) fn record_seed(hash: u64, seed: i64) {
+    SEEDS.with(|seeds| {
+        seeds.borrow_mut().insert(hash, seed);
+    });
+}
+
+pub(crate) fn forget_seed(hash: u64) {
+    SEEDS.with(|seeds| {
+        seeds.borrow_mut().remove(&hash);
+    });
+}
+
+/// The element without the markers of Helmet, so it can be passed to a Helmet again.
+fn element_map(element: &web_sys::Element) -> OwnedElementMap {
+    let attributes = element
+        .get_attribute_names()
+        .iter()
+        .filter_map(|name| name.as_string())
+        .filter(|name| !MARKERS.contains(&name.as_str()))
+        .filter_map(|name| {
+            let value = element.get_attribute(&name)?;
+            Some((name, value))
+        })
+        .collect();
+    let inner_html = element.inner_html();
+
+    OwnedElementMap {
+        tag: element.tag_name().to_lowercase(),
+        attributes,
+        inner_html: (!inner_html.is_empty()).then_some(inner_html),
+    }
+}
@@ -0,0 +1,36 @@
+use crate::lock_init_cache;
+use rustc_hash::FxHashMap;
+use std::{cell::RefCell, sync::Arc};
+
+thread_local! {
+    static MOUNTED: RefCell<FxHashMap<usize, Arc<dyn Fn() + Send + Sync>>> =
+        RefCell::new(FxHashMap::default());
+}
+
+/// Makes every mounted Helmet inject its elements again, e.g. after a hot-reload.
+///
+/// The elements stay in the head until they are replaced by the next render.
+pub fn invalidate() {
+    if let Some(mut init_cache) = lock_init_cache() {
+        init_cache.clear();
+    }
+
+    let schedulers = MOUNTED.with(|mounted| mounted.borrow().values().cloned().collect::<Vec<_>>());
+
+    schedulers
+        .iter()
+        .for_each(|schedule_update| schedule_update());
+}
+
+pub(crate) fn mount(instance: usize, schedule_update: Arc<dyn Fn() + Send + Sync>) {
+    MOUNTED.with(|mounted| {
+        mounted.borrow_mut().insert(instance, schedule_update);
+    });
+}
+
+#[cfg(not(feature = "ssr-only"))]
+pub(crate) fn unmount(instance: usize) {
+    MOUNTED.with(|mounted| {
+        mounted.borrow_mut().remove(&instance);
+    });
+}
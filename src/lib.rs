@@ -44,196 +44,1529 @@
 //! Any children passed to the helmet component will then be placed in the `<head></head>` of your document.
 //!
 //! They will be visible while the component is rendered. Duplicates **won't** get appended multiple times.
+//!
+//! Helmets with the same `seed` share their duplicates. Pass `seed: call_site_seed!()` to keep the elements of each place Helmet is used apart.
+//!
+//! Lists of elements, e.g. from a `for` loop, an iterator of `rsx!` nodes or a function returning several of them, are placed in the head as well. Components aren't rendered within Helmet, so their elements are left out. An element with `"enabled": false` is left out as well, e.g. `meta { "enabled": is_preview, name: "robots", content: "noindex" }`. An element with `"critical": true` is injected right away, ahead of `defer_until_idle` or a running transaction, while one with `"critical": false` waits for the browser to be idle.
+//!
+//! Attributes are passed through as they are, including newer ones like `fetchpriority="high"` or `blocking="render"`. They may be dynamic as well, e.g. `style { media: "{query}", ... }`, which together with a `data-helmet-key` replaces the element in place whenever the value changes.
+//!
+//! Elements which may only exist once, like `<base>`, `<link rel="manifest">`, an icon of a given `sizes` or `<meta http-equiv="content-type">`, replace their previous version in place.
+//!
+//! The same goes for elements sharing a `data-helmet-key` attribute. Use `on_remove` with that key to run some cleanup right before such an element is removed, or `on_remove_async` to keep it until an async teardown is done. `on_event` listens to the events of such elements, e.g. the `load` of a script.
+//!
+//! ## Builder
+//! Elements can also be built in code with [`HeadBuilder`] and passed as `elements`, e.g. `HeadBuilder::new().link(Rel::Stylesheet, "/style.css").build()`. The `meta!` macro builds `<meta>` elements and refuses to compile without their required attributes.
+//!
+//! ## Web Workers
+//! There is no document inside a Web Worker, so Helmet does nothing there by default. Use [`set_worker_sink`] to receive its changes as [`WorkerMessage`]s instead, e.g. to forward them to the main thread.
+//!
+//! ## Server side rendering
+//! Provide a [`HeadCollector`] as context at the root of your app. Helmet then registers its elements there instead of the DOM, and `flush_head()` returns them as html, ready to be streamed before the body.
+//!
+//! When hydrating, elements a `HeadCollector` already placed in the head are adopted instead of being injected a second time. They are recognized by their `data-helmet-hash`, so elements the page wrote itself, e.g. into its index.html, are left alone. Render `<html data-helmet-hydrating>` on the server to hold back every injection until `hydrated()` is called.
+//!
+//! Builds which only render on the server can enable the `ssr-only` feature, which leaves out the removal of elements when a Helmet is dropped.
+
+mod adopted;
+mod builder;
+mod callbacks;
+mod cleanup;
+mod document;
+#[cfg(feature = "lifecycle-log")]
+mod events;
+mod guard;
+mod hooks;
+mod hydration;
+mod idle;
+mod inject;
+mod inspect;
+mod invalidate;
+mod listeners;
+mod load;
+mod memo;
+mod observer;
+mod owners;
+mod policy;
+mod sanitize;
+mod ssr;
+mod title;
+mod tokens;
+mod transaction;
+mod validate;
+mod worker;
 
-use dioxus::prelude::*;
+pub use adopted::StyleBackend;
+pub use builder::{AppleWebApp, HeadBuilder, Rel};
+pub use callbacks::{on_remove, on_remove_async};
+pub use cleanup::{remove_scope, set_cleanup_delay};
+pub use document::DocumentHandle;
+#[cfg(feature = "lifecycle-log")]
+pub use events::{take_events, HelmetEvent};
+pub use guard::{set_injection_limit, InjectionLimit};
+pub use hooks::{use_head, use_title};
+pub use hydration::hydrated;
+pub use inject::{inject, inject_google_fonts, inject_meta, inject_with, remove_meta, HeadGuard};
+pub use inspect::{head_elements, head_snapshot, injected_elements, HeadElementInfo};
+pub use invalidate::invalidate;
+pub use listeners::on_event;
+pub use load::{
+    inject_script, inject_stylesheet, inject_stylesheet_with_fallback, prefetch, preload_font,
+};
+pub use observer::observe_head;
+pub use policy::{set_content_policy, set_duplicate_policy, ContentSetter, DuplicatePolicy};
+pub use ssr::{element_map_to_html, HeadCollector, RenderMode};
+pub use title::TitleMode;
+pub use tokens::inject_tokens;
+pub use transaction::{transaction, Transaction};
+pub use worker::{is_worker, set_worker_sink, WorkerMessage};
+
+use dioxus::{
+    core::{Attribute, AttributeValue, DynamicNode},
+    prelude::*,
+};
 use lazy_static::lazy_static;
 use rustc_hash::FxHasher;
+#[cfg(not(feature = "ssr-only"))]
+use std::time::Duration;
 use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fmt::Write,
     hash::{Hash, Hasher},
-    sync::Mutex,
+    sync::{Arc, Mutex, MutexGuard, TryLockError},
+    thread::LocalKey,
 };
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// Marks the elements of this version, see [`ElementMap::content_hash`].
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+lazy_static! {
+    static ref INIT_CACHE: Mutex<Vec<u64>> = Mutex::new(Vec::new());
+}
+
+/// Locks the cache, recovering it if a previous holder panicked.
+fn lock_init_cache() -> Option<MutexGuard<'static, Vec<u64>>> {
+    match INIT_CACHE.try_lock() {
+        Ok(init_cache) => Some(init_cache),
+        Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+        Err(TryLockError::WouldBlock) => None,
+    }
+}
+
+/// Forgets every element Helmet injected, so each test starts with an empty cache.
+/// Doesn't touch the DOM.
+#[cfg(feature = "test-utils")]
+pub fn reset_cache() {
+    INIT_CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clear();
+    owners::clear();
+}
+
+#[derive(Props)]
+pub struct HelmetProps<'a> {
+    #[props(default = 0)]
+    seed: i64,
+    /// Sets the title of the document, taking precedence over a `title` child.
+    title: Option<String>,
+    /// Injects a `<meta name="description">`, replacing the one of another Helmet.
+    description: Option<String>,
+    /// Injects a `<meta name="keywords">`, replacing the one of another Helmet.
+    #[props(default)]
+    keywords: Vec<String>,
+    /// Injects a `<meta name="author">`, replacing the one of another Helmet.
+    author: Option<String>,
+    /// Injects a `<meta name="referrer">`, e.g. `no-referrer`, replacing the one of another Helmet.
+    referrer: Option<String>,
+    /// Injects a `<meta name="robots">`, e.g. `noindex, nofollow`, replacing the one of another Helmet.
+    robots: Option<String>,
+    /// What happens to a title which is already set, e.g. by another Helmet.
+    #[props(default)]
+    title_mode: TitleMode,
+    /// Inject the elements once the browser is idle, e.g. for analytics scripts which shouldn't block the first paint.
+    #[props(default)]
+    defer_until_idle: bool,
+    /// Nest the css of style elements in the class from [`scope_class`] with the seed of this Helmet,
+    /// so it only applies to elements within that class.
+    #[props(default)]
+    scoped: bool,
+    /// Insert the elements at this index of the head, e.g. `0` to make them its first children.
+    index: Option<usize>,
+    /// Place the elements in the first element matching this selector instead of the head,
+    /// e.g. a slot of a design system. The title still goes into the head.
+    target_selector: Option<String>,
+    /// Elements built outside of rsx, e.g. with [`HeadBuilder`].
+    #[props(default)]
+    elements: Vec<OwnedElementMap>,
+    /// Set the attributes on an element this Helmet replaces, e.g. a meta with the same
+    /// `data-helmet-key`, instead of replacing it, so attributes added by other code are kept.
+    #[props(default)]
+    merge_attributes: bool,
+    /// Whether the elements go into the DOM or a [`HeadCollector`], e.g. to pick at runtime in universal apps.
+    #[props(default)]
+    mode: RenderMode,
+    /// Drop scripts, event handlers and `javascript:` urls and escape the content of the elements,
+    /// e.g. when they contain values from users.
+    #[props(default)]
+    sanitize: bool,
+    /// Bump it whenever the elements change, so renders in between reuse their hashes
+    /// instead of hashing every element again.
+    version: Option<u64>,
+    /// Remove the elements before the drop of this Helmet returns, never batched with
+    /// a transaction, delayed by a cleanup delay or waiting for an async teardown, e.g. in tests.
+    #[props(default)]
+    #[cfg_attr(feature = "ssr-only", allow(dead_code))]
+    synchronous_cleanup: bool,
+    /// Keep elements with a key, e.g. a `data-helmet-key`, when the parent renders this Helmet
+    /// again, updating them in place instead of removing and injecting them again.
+    #[props(default)]
+    stable_keys: bool,
+    /// How the css of style elements gets into the document, a style element where
+    /// constructable stylesheets aren't supported.
+    #[props(default)]
+    style_backend: StyleBackend,
+    /// Only compute the elements and their hashes and pass them to `on_update`,
+    /// leaving the head as it is.
+    #[props(default)]
+    dry_run: bool,
+    /// Derives the key of the elements without a `data-helmet-key`, so elements with the same key
+    /// replace each other, e.g. metas by their `name`.
+    ///
+    /// ```rust
+    /// Helmet {
+    ///     key_fn: |element: &OwnedElementMap| {
+    ///         let attribute = |name| element.attributes.iter().find(|(n, _)| n == name).map(|(_, v)| v);
+    ///         match element.tag.as_str() {
+    ///             "meta" => attribute("name").map(|name| format!("meta[name={name}]")),
+    ///             "link" => Some(format!("link[rel={}][href={}]", attribute("rel")?, attribute("href")?)),
+    ///             _ => None,
+    ///         }
+    ///     },
+    /// }
+    /// ```
+    key_fn: Option<fn(&OwnedElementMap) -> Option<String>>,
+    /// How often to render again on the next tick while the document has no head yet,
+    /// e.g. early in the handoff from server side rendering.
+    #[props(default)]
+    head_retries: u32,
+    /// Attributes added to every element of this Helmet which doesn't have them itself,
+    /// e.g. a `data-testid`. They don't tell its elements apart from the ones of other Helmets.
+    #[props(default)]
+    extra_attributes: HashMap<String, String>,
+    /// Receives the hash and element of everything this Helmet injects, or would inject on a `dry_run`.
+    on_update: Option<EventHandler<'a, Vec<(u64, OwnedElementMap)>>>,
+    children: Element<'a>,
+}
+
+impl HelmetProps<'_> {
+    /// Identifies this instance of the props until they are dropped.
+    fn instance(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    /// The element with the `extra_attributes` it doesn't have itself, sorted by name.
+    fn with_extra_attributes<'a>(&'a self, element_map: &ElementMap<'a>) -> ElementMap<'a> {
+        let mut element_map = element_map.clone();
+
+        let mut extra_attributes = self
+            .extra_attributes
+            .iter()
+            .filter(|(name, _)| element_map.attribute(name).is_none())
+            .map(|(name, value)| (name.as_str(), Cow::Borrowed(value.as_str())))
+            .collect::<Vec<_>>();
+        extra_attributes.sort_unstable();
+
+        element_map.attributes.extend(extra_attributes);
+        element_map
+    }
+
+    /// The `title` prop, or else the text of the last `<title>` among the elements,
+    /// as browsers only honor one.
+    fn title(&self) -> Option<Cow<'_, str>> {
+        if let Some(title) = self.title.as_deref() {
+            return Some(Cow::Borrowed(title));
+        }
+
+        extract_element_maps(&self.children)
+            .into_iter()
+            .chain(self.elements.iter().map(OwnedElementMap::as_element_map))
+            .rfind(|element_map| element_map.tag == "title")
+            .map(|element_map| element_map.inner_html.unwrap_or_default())
+    }
+
+    /// The metas of the `description`, `keywords` and `author` props.
+    fn meta_element_maps(&self) -> impl Iterator<Item = ElementMap<'_>> {
+        let keywords = (!self.keywords.is_empty()).then(|| Cow::Owned(self.keywords.join(", ")));
+
+        [
+            (
+                "description",
+                self.description.as_deref().map(Cow::Borrowed),
+            ),
+            ("keywords", keywords),
+            ("author", self.author.as_deref().map(Cow::Borrowed)),
+            ("referrer", self.referrer.as_deref().map(Cow::Borrowed)),
+            ("robots", self.robots.as_deref().map(Cow::Borrowed)),
+        ]
+        .into_iter()
+        .filter_map(|(name, content)| {
+            Some(ElementMap {
+                tag: "meta",
+                attributes: vec![
+                    ("name", Cow::Borrowed(name)),
+                    ("content", content?),
+                    ("data-helmet-key", Cow::Owned(format!("meta[name={name}]"))),
+                ],
+                inner_html: None,
+            })
+        })
+    }
+
+    fn element_maps(&self) -> Vec<ElementMap<'_>> {
+        let element_maps = extract_element_maps(&self.children)
+            .into_iter()
+            .chain(self.elements.iter().map(OwnedElementMap::as_element_map))
+            // set as the title of the document instead, see `title`
+            .filter(|element_map| element_map.tag != "title")
+            .chain(self.meta_element_maps())
+            .filter_map(|element_map| {
+                if self.sanitize {
+                    sanitize::sanitize(element_map)
+                } else {
+                    Some(element_map)
+                }
+            })
+            .map(|mut element_map| {
+                if let Some(key_fn) = self.key_fn {
+                    if element_map.attribute("data-helmet-key").is_none() {
+                        if let Some(key) = key_fn(&(&element_map).into()) {
+                            element_map
+                                .attributes
+                                .push(("data-helmet-key", Cow::Owned(key)));
+                        }
+                    }
+                }
+
+                element_map
+            });
+
+        if !self.scoped {
+            return element_maps.collect();
+        }
+
+        let class = scope_class(self.seed);
+
+        element_maps
+            .map(|mut element_map| {
+                if element_map.tag == "style" {
+                    element_map.inner_html = element_map
+                        .inner_html
+                        .map(|css| Cow::Owned(format!(".{class} {{ {css} }}")));
+                }
+
+                element_map
+            })
+            .collect()
+    }
+}
+
+/// The class a `scoped` Helmet with the given seed limits its styles to.
+///
+/// ```rust
+/// rsx! {
+///     Helmet { seed: 1, scoped: true, style { "p {{ color: red; }}" } }
+///     div { class: "{scope_class(1)}", p { "red" } }
+/// }
+/// ```
+pub fn scope_class(seed: i64) -> String {
+    let mut hasher = FxHasher::default();
+    seed.hash(&mut hasher);
+    format!("helmet-{:x}", hasher.finish())
+}
+
+/// A seed unique to where it is written, so Helmets with the same elements in different
+/// components don't share them without picking seeds by hand.
+///
+/// ```rust
+/// rsx! {
+///     Helmet { seed: call_site_seed!(), style { "p {{ color: red; }}" } }
+/// }
+/// ```
+#[macro_export]
+macro_rules! call_site_seed {
+    () => {
+        $crate::seed_from_location(file!(), line!(), column!())
+    };
+}
+
+#[doc(hidden)]
+pub fn seed_from_location(file: &str, line: u32, column: u32) -> i64 {
+    let mut hasher = FxHasher::default();
+    (file, line, column).hash(&mut hasher);
+    hasher.finish() as i64
+}
+
+#[allow(non_snake_case)]
+pub fn Helmet<'a>(cx: Scope<'a, HelmetProps<'a>>) -> Element<'a> {
+    // the renders without a head since the last one with it
+    let head_retries = cx.use_hook(|| Cell::new(0));
+    let memo = cx.use_hook(memo::Memo::default);
+
+    // the props are dropped on every render of the parent, the scope only once it is gone
+    use_on_destroy(cx, {
+        let scope = cx.scope_id().0;
+        let handle = cx.consume_context::<DocumentHandle>();
+        move || {
+            guard::forget(scope);
+            pop_breadcrumb(scope, handle);
+        }
+    });
+
+    let element_maps = cx.props.element_maps();
+
+    if cfg!(debug_assertions) {
+        validate::validate(cx.props.title().as_deref(), &element_maps);
+    }
+
+    let handle = cx.consume_context::<DocumentHandle>();
+
+    // the same elements hash the same, whether they are collected, sent, injected or only reported
+    let seed = match &handle {
+        Some(handle) => handle.seed(cx.props.seed),
+        None => cx.props.seed,
+    };
+
+    if cx.props.dry_run {
+        if let Some(on_update) = &cx.props.on_update {
+            on_update.call(
+                element_maps
+                    .iter()
+                    .map(|element_map| (element_hash(seed, element_map), element_map.into()))
+                    .collect(),
+            );
+        }
+
+        return None;
+    }
+
+    let collector = match cx.props.mode {
+        RenderMode::Dom => None,
+        RenderMode::Auto => cx.consume_context::<HeadCollector>(),
+        RenderMode::SsrCollect => {
+            let collector = cx.consume_context::<HeadCollector>();
+
+            if collector.is_none() {
+                log::warn!(
+                    "dioxus-helmet: `RenderMode::SsrCollect` needs a provided HeadCollector"
+                );
+                return None;
+            }
+
+            collector
+        }
+    };
+
+    if let Some(collector) = collector {
+        if let Some(title) = cx.props.title() {
+            collector.set_title(&title, cx.props.title_mode);
+        }
+
+        element_maps.iter().for_each(|element_map| {
+            let hash = element_hash(seed, element_map);
+            collector.register(hash, &cx.props.with_extra_attributes(element_map));
+        });
+
+        return None;
+    }
+
+    let document = match handle {
+        Some(handle) => {
+            let document = handle.document().clone();
+            document::remember(cx.props.instance(), handle);
+            document
+        }
+        None => {
+            let Some(document) = document() else {
+                worker::send_injections(seed, cx.props.title().as_deref(), &element_maps);
+
+                return None;
+            };
+
+            document
+        }
+    };
+    let Some(head) = document.head() else {
+        if head_retries.get() < cx.props.head_retries {
+            head_retries.set(head_retries.get() + 1);
+            render_next_tick(cx.schedule_update());
+        }
+
+        return None;
+    };
+    head_retries.set(0);
+
+    if let Some(title) = cx.props.title() {
+        let title = title.as_ref();
+
+        match cx.props.title_mode {
+            TitleMode::Breadcrumb(separator) => {
+                let title = title::push_breadcrumb(
+                    cx.scope_id().0,
+                    cx.props.seed,
+                    separator,
+                    title,
+                    title_of(&head),
+                );
+                apply_title(&document, &head, &title, TitleMode::Overwrite);
+            }
+            mode => apply_title(&document, &head, title, mode),
+        }
+    }
+
+    let parent = match cx.props.target_selector.as_deref() {
+        Some(selector) => {
+            let Some(target) = document.query_selector(selector).ok().flatten() else {
+                log::warn!("dioxus-helmet: no element matches the target selector `{selector}`");
+                return None;
+            };
+
+            target
+        }
+        None => head.into(),
+    };
+
+    invalidate::mount(cx.props.instance(), cx.schedule_update());
+
+    let mut init_cache = lock_init_cache()?;
+
+    let scope = cx.scope_id().0.to_string();
+
+    let insert = |init_cache: &mut Vec<u64>,
+                  element_map: &ElementMap,
+                  hash: u64,
+                  position: usize| {
+        // keeps the elements in order when they are placed at an index
+        let index = cx.props.index.map(|index| index + position);
+
+        // added after hashing, so the same element of another scope is still a duplicate
+        let mut element_map = cx.props.with_extra_attributes(element_map);
+        element_map
+            .attributes
+            .push(("data-helmet-scope", Cow::Owned(scope.clone())));
+        let element_map = &element_map;
+
+        let placement = Placement {
+            index,
+            merge_attributes: cx.props.merge_attributes || cx.props.stable_keys,
+        };
+
+        if cx.props.style_backend == StyleBackend::Constructable
+            && element_map.tag == "style"
+            && adopted::adopt(
+                &document,
+                hash,
+                element_map.inner_html.as_deref().unwrap_or_default(),
+            )
+        {
+            return;
+        }
+
+        // injected once hydrated, see `hydrated`
+        if hydration::defer(&document, || {
+            PendingInsertion::new(&document, &parent, hash, element_map, placement)
+        }) {
+            return;
+        }
+
+        // `"critical": true` injects right away, `"critical": false` once the browser is idle
+        let critical = element_map
+            .attribute("data-helmet-critical")
+            .map(|critical| critical != "false");
+
+        if critical == Some(true) {
+            insert_element(&document, &parent, init_cache, element_map, hash, placement);
+        } else if critical.map_or(cx.props.defer_until_idle, |critical| !critical) {
+            idle::defer(PendingInsertion::new(
+                &document,
+                &parent,
+                hash,
+                element_map,
+                placement,
+            ));
+        } else if !transaction::defer_insertion(&document, &parent, hash, element_map, placement) {
+            insert_element(&document, &parent, init_cache, element_map, hash, placement);
+        }
+    };
+
+    let hashes = memo.hashes(cx.props.version, seed, || {
+        element_maps
+            .iter()
+            .map(|element_map| element_hash(seed, element_map))
+            .collect()
+    });
+    memo::remember(cx.props.instance(), &hashes);
+
+    if let Some(on_update) = &cx.props.on_update {
+        on_update.call(
+            hashes
+                .iter()
+                .zip(&element_maps)
+                .map(|(hash, element_map)| (*hash, element_map.into()))
+                .collect(),
+        );
+    }
+
+    element_maps
+        .iter()
+        .zip(hashes)
+        .enumerate()
+        .for_each(|(position, (element_map, hash))| {
+            if owners::claim(hash, cx.props.instance()).is_some() && cfg!(debug_assertions) {
+                log::warn!(
+                    "dioxus-helmet: another Helmet already injected the same <{}> with seed {}, \
+                 it is shared until both of them are dropped. \
+                 Give them different seeds to keep them apart.",
+                    element_map.tag,
+                    cx.props.seed
+                );
+            }
+
+            if !init_cache.contains(&hash) {
+                if !guard::allow(cx.scope_id().0, element_map.tag) {
+                    return;
+                }
+
+                init_cache.push(hash);
+                // the seed of the user, not the one derived for the document
+                inspect::record_seed(hash, cx.props.seed);
+
+                if !cleanup::revive(hash) {
+                    insert(&mut init_cache, element_map, hash, position);
+                }
+
+                return;
+            }
+
+            match policy::duplicate_policy() {
+                DuplicatePolicy::Skip => {}
+                DuplicatePolicy::Warn => {
+                    log::warn!(
+                        "dioxus-helmet: skipped duplicate <{}> (data-helmet-id={hash})",
+                        element_map.tag
+                    );
+                }
+                DuplicatePolicy::Replace => {
+                    // merged into the existing element instead
+                    let merge = cx.props.merge_attributes || cx.props.stable_keys;
+
+                    if !merge && !transaction::defer_removal(&document, hash) {
+                        remove_elements(&document, hash);
+                    }
+
+                    insert(&mut init_cache, element_map, hash, position);
+                }
+            }
+        });
+
+    None
+}
+
+/// Takes back the part of the title of a dropped Helmet with [`TitleMode::Breadcrumb`].
+fn pop_breadcrumb(scope: usize, handle: Option<DocumentHandle>) {
+    let Some(title) = title::pop_breadcrumb(scope) else {
+        return;
+    };
+    let Some(document) = handle
+        .map(|handle| handle.document().clone())
+        .or_else(document)
+    else {
+        return;
+    };
+    let Some(head) = document.head() else {
+        return;
+    };
+
+    apply_title(&document, &head, &title, TitleMode::Overwrite);
+}
+
+fn render_next_tick(schedule_update: Arc<dyn Fn() + Send + Sync>) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+
+    let callback = Closure::once_into_js(move || schedule_update());
+    let _ = window.set_timeout_with_callback(callback.unchecked_ref());
+}
+
+/// Applies every change which is still waiting, e.g. for the browser to be idle,
+/// a running [`Transaction`] or the end of hydration, which ends with it.
+///
+/// Handy right before taking a screenshot of the page.
+pub fn flush() {
+    transaction::flush();
+    idle::flush();
+    hydration::hydrated();
+}
+
+/// Sets the title of the document, the same way the `title` prop of Helmet does,
+/// e.g. from an event handler.
+pub fn set_title(title: &str) {
+    let Some(document) = document() else {
+        return;
+    };
+    let Some(head) = document.head() else {
+        return;
+    };
+
+    apply_title(&document, &head, title, TitleMode::Overwrite);
+}
+
+/// Takes back the part of the title of the Helmets with the seed and their [`TitleMode::Breadcrumb`],
+/// e.g. `App > Settings` becomes `App`, as if they were dropped.
+///
+/// They append their part again when they render the next time.
+pub fn restore_title_for(seed: i64) {
+    let Some(title) = title::pop_breadcrumbs_of(seed) else {
+        return;
+    };
+
+    set_title(&title);
+}
+
+/// The text of the `<title>` in the head, none without one.
+pub fn current_title() -> Option<String> {
+    title_of(&document()?.head()?)
+}
+
+fn title_of(head: &web_sys::HtmlHeadElement) -> Option<String> {
+    head.get_elements_by_tag_name("title")
+        .get_with_index(0)?
+        .text_content()
+}
+
+fn apply_title(
+    document: &web_sys::Document,
+    head: &web_sys::HtmlHeadElement,
+    title: &str,
+    mode: TitleMode,
+) {
+    let node = head.get_elements_by_tag_name("title").get_with_index(0);
+    let current = node.as_ref().and_then(|node| node.text_content());
+
+    let Some(title) = mode.resolve(current.as_deref(), title) else {
+        return;
+    };
+
+    if let Some(node) = node {
+        node.set_inner_html(&title);
+    } else {
+        let node = document.create_element("title").unwrap();
+
+        node.set_inner_html(&title);
+
+        head.append_child(&node).unwrap();
+    };
+
+    // the <title> exists by now, so this doesn't create another one
+    document.set_title(&title);
+}
+
+/// The document to place elements in, if there is one.
+///
+/// Outside of wasm there never is, as web_sys would panic there.
+fn document() -> Option<web_sys::Document> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        web_sys::window().and_then(|window| window.document())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        None
+    }
+}
+
+/// Places a single element outside of a Helmet, going through the same cache.
+///
+/// Returns the element in the head, whether it was placed just now or already before.
+fn inject_owned(
+    seed: i64,
+    element: &OwnedElementMap,
+    placement: Placement,
+) -> Option<(u64, web_sys::Element)> {
+    let document = document()?;
+    let head = document.head()?;
+    let element_map = element.as_element_map();
+    let hash = element_hash(seed, &element_map);
+
+    let mut init_cache = lock_init_cache()?;
+
+    let injected = init_cache.contains(&hash);
+
+    if !injected {
+        init_cache.push(hash);
+        inspect::record_seed(hash, seed);
+    }
+
+    // a lingering element of a dropped Helmet is taken over before its cleanup delay removes it
+    let element = if injected || cleanup::revive(hash) {
+        document
+            .query_selector(&format!("[data-helmet-id='{hash}']"))
+            .ok()
+            .flatten()
+    } else {
+        insert_element(
+            &document,
+            &head,
+            &mut init_cache,
+            &element_map,
+            hash,
+            placement,
+        )
+    };
+
+    element.map(|element| (hash, element))
+}
+
+/// An insertion which waits, e.g. for a transaction to be committed or the browser to be idle.
+struct PendingInsertion {
+    document: web_sys::Document,
+    parent: web_sys::Element,
+    hash: u64,
+    element: OwnedElementMap,
+    placement: Placement,
+}
+
+impl PendingInsertion {
+    fn new(
+        document: &web_sys::Document,
+        parent: &web_sys::Element,
+        hash: u64,
+        element_map: &ElementMap,
+        placement: Placement,
+    ) -> Self {
+        Self {
+            document: document.clone(),
+            parent: parent.clone(),
+            hash,
+            element: element_map.into(),
+            placement,
+        }
+    }
+
+    fn apply(&self, init_cache: &mut Vec<u64>) {
+        insert_element(
+            &self.document,
+            &self.parent,
+            init_cache,
+            &self.element.as_element_map(),
+            self.hash,
+            self.placement,
+        );
+    }
+
+    /// Inserts the queued elements which are still in the cache, as the ones removed from it
+    /// were cleaned up in the meantime.
+    fn apply_queued(queue: &'static LocalKey<RefCell<Vec<PendingInsertion>>>) {
+        let pending = queue.with(|queue| std::mem::take(&mut *queue.borrow_mut()));
+
+        if pending.is_empty() {
+            return;
+        }
+
+        let Some(mut init_cache) = lock_init_cache() else {
+            return;
+        };
+
+        pending.iter().for_each(|insertion| {
+            if init_cache.contains(&insertion.hash) {
+                insertion.apply(&mut init_cache);
+            }
+        });
+    }
+}
+
+/// Where and how an element gets into the head.
+#[derive(Debug, Clone, Copy, Default)]
+struct Placement {
+    /// The index in the head, its end without one.
+    index: Option<usize>,
+    /// Set the attributes on the element being replaced instead of replacing it.
+    merge_attributes: bool,
+}
+
+/// Places the element in the parent, usually the head, as given by the placement.
+fn insert_element(
+    document: &web_sys::Document,
+    parent: &web_sys::Element,
+    init_cache: &mut Vec<u64>,
+    element_map: &ElementMap,
+    hash: u64,
+    placement: Placement,
+) -> Option<web_sys::Element> {
+    if element_map.is_font_preload() && element_map.attribute("crossorigin").is_none() {
+        log::warn!(
+            "dioxus-helmet: font preloads are ignored without a crossorigin attribute, \
+             use `preload_font` to set it"
+        );
+    }
+
+    // rendered on the server already, so it only needs to be marked as ours
+    if let Some(adopted) = find_server_rendered(parent, init_cache, element_map) {
+        adopted
+            .set_attribute("data-helmet-id", &hash.to_string())
+            .ok()?;
+
+        if let Some(key) = element_map.singleton_key() {
+            adopted.set_attribute("data-helmet-key", &key).ok()?;
+        }
+
+        #[cfg(feature = "lifecycle-log")]
+        events::record(events::HelmetEvent::Inject(
+            hash,
+            element_map.tag.to_owned(),
+        ));
+
+        listeners::attach(&adopted, hash);
+        load::record_adopted(document, &adopted);
+
+        return Some(adopted);
+    }
+
+    // injected by another version of this crate, which also removes it
+    if let Some(foreign) = find_foreign(parent, element_map) {
+        return Some(foreign);
+    }
+
+    // the same element may have been left behind by `invalidate`
+    let existing = document
+        .query_selector(&format!("[data-helmet-id='{hash}']"))
+        .ok()
+        .flatten()
+        .or_else(|| {
+            element_map
+                .singleton_key()
+                .and_then(|key| find_singleton(parent, &key))
+        });
+
+    let new_element = if let Some(existing) = existing {
+        // the previous occupant loses its place, so forget it was injected
+        if let Some(existing_hash) = existing
+            .get_attribute("data-helmet-id")
+            .and_then(|id| id.parse::<u64>().ok())
+            .filter(|&existing_hash| existing_hash != hash)
+        {
+            init_cache.retain(|&c| c != existing_hash);
+            listeners::detach(existing_hash);
+        }
+
+        if placement.merge_attributes {
+            // keeps the attributes other code added to it
+            element_map.apply_to(&existing, &hash);
+            existing
+        } else {
+            let new_element = element_map.try_into_element(document, &hash)?;
+            existing.replace_with_with_node_1(&new_element).ok()?;
+            new_element
+        }
+    } else {
+        let new_element = element_map.try_into_element(document, &hash)?;
+        // the charset has to come before the title and anything else with text
+        let index = match placement.index {
+            None if element_map.is_charset() => Some(0),
+            index => index,
+        };
+        let reference = index
+            .and_then(|index| u32::try_from(index).ok())
+            .and_then(|index| parent.children().item(index));
+
+        parent
+            .insert_before(&new_element, reference.as_ref().map(|el| el.as_ref()))
+            .ok()?;
+        new_element
+    };
+
+    #[cfg(feature = "lifecycle-log")]
+    events::record(events::HelmetEvent::Inject(
+        hash,
+        element_map.tag.to_owned(),
+    ));
+
+    listeners::attach(&new_element, hash);
+    load::record(&new_element);
+
+    Some(new_element)
+}
+
+fn remove_elements(document: &web_sys::Document, hash: u64) {
+    remove_elements_with(document, hash, true);
+}
+
+/// Removes the elements of the hash, without waiting for an async teardown unless `wait_for_teardown`.
+fn remove_elements_with(document: &web_sys::Document, hash: u64, wait_for_teardown: bool) {
+    listeners::detach(hash);
+    adopted::release(document, hash);
+    inspect::forget_seed(hash);
+
+    if let Ok(children) = document.query_selector_all(&format!("[data-helmet-id='{hash}']")) {
+        if let Ok(Some(children_iter)) = js_sys::try_iter(&children) {
+            children_iter.for_each(|child| {
+                if let Ok(child) = child {
+                    let el = web_sys::Element::from(child);
+                    callbacks::run_on_remove(&el);
+
+                    match callbacks::run_on_remove_async(&el) {
+                        Some(teardown) if wait_for_teardown => {
+                            // not ours anymore, a new version may be injected in the meantime
+                            let _ = el.remove_attribute("data-helmet-id");
+                            let el = el.clone();
+                            wasm_bindgen_futures::spawn_local(async move {
+                                teardown.await;
+                                el.remove();
+                            });
+                        }
+                        Some(teardown) => {
+                            wasm_bindgen_futures::spawn_local(teardown);
+                            el.remove();
+                        }
+                        None => el.remove(),
+                    }
+
+                    #[cfg(feature = "lifecycle-log")]
+                    events::record(events::HelmetEvent::Remove(
+                        hash,
+                        el.tag_name().to_lowercase(),
+                    ));
+                };
+            });
+        }
+    }
+}
+
+/// An element of the parent which a [`HeadCollector`] rendered with the same content on the server,
+/// and no Helmet on the client took over yet.
+///
+/// Elements the page itself wrote, e.g. into its index.html, have no `data-helmet-hash`,
+/// so they are never adopted and never removed.
+fn find_server_rendered(
+    parent: &web_sys::Element,
+    init_cache: &[u64],
+    element_map: &ElementMap,
+) -> Option<web_sys::Element> {
+    let content_hash = element_map.content_hash();
+    let children = parent.children();
+
+    (0..children.length())
+        .filter_map(|index| children.item(index))
+        .filter(|el| el.tag_name().eq_ignore_ascii_case(element_map.tag))
+        .filter(|el| el.get_attribute("data-helmet-hash").as_deref() == Some(content_hash.as_str()))
+        .find(|el| {
+            !el.get_attribute("data-helmet-id")
+                .and_then(|id| id.parse::<u64>().ok())
+                .is_some_and(|id| init_cache.contains(&id))
+        })
+}
+
+/// An element of the parent with the same content, injected by another version of this crate.
+fn find_foreign(parent: &web_sys::Element, element_map: &ElementMap) -> Option<web_sys::Element> {
+    let content_hash = element_map.content_hash();
+    let children = parent.children();
+
+    (0..children.length())
+        .filter_map(|index| children.item(index))
+        .filter(|el| el.get_attribute("data-helmet-hash").as_deref() == Some(content_hash.as_str()))
+        .find(|el| el.get_attribute("data-helmet-version").as_deref() != Some(VERSION))
+}
+
+fn find_singleton(parent: &web_sys::Element, key: &str) -> Option<web_sys::Element> {
+    let children = parent.children();
+
+    (0..children.length())
+        .filter_map(|index| children.item(index))
+        .find(|el| el.get_attribute("data-helmet-key").as_deref() == Some(key))
+}
+
+// rendered once on the server, so there is nothing to remove
+#[cfg(not(feature = "ssr-only"))]
+impl Drop for HelmetProps<'_> {
+    fn drop(&mut self) {
+        if self.dry_run {
+            return;
+        }
+
+        invalidate::unmount(self.instance());
+
+        let element_maps = self.element_maps();
+        // the hashes the render claimed, the content may have changed without a new version
+        let claimed = memo::forget(self.instance());
+
+        let (seed, document) = match document::forget(self.instance()) {
+            Some(handle) => (handle.seed(self.seed), handle.document().clone()),
+            None => {
+                let Some(document) = document() else {
+                    worker::send_removals(self.seed, &element_maps);
+                    return;
+                };
+
+                (self.seed, document)
+            }
+        };
+
+        let hashes = claimed.unwrap_or_else(|| {
+            element_maps
+                .iter()
+                .map(|element_map| element_hash(seed, element_map))
+                .collect()
+        });
+
+        let Some(mut init_cache) = lock_init_cache() else {
+            return;
+        };
+
+        element_maps
+            .iter()
+            .zip(hashes)
+            .for_each(|(element_map, hash)| {
+                // still in use by another Helmet
+                if !owners::release(hash, self.instance()) {
+                    return;
+                }
+
+                if let Some(index) = init_cache.iter().position(|&c| c == hash) {
+                    init_cache.remove(index);
+                }
+
+                idle::cancel(hash);
+                hydration::cancel(hash);
+
+                if self.synchronous_cleanup {
+                    remove_elements_with(&document, hash, false);
+                } else if self.stable_keys && element_map.singleton_key().is_some() {
+                    // kept for the next render, which updates it by its key
+                    if !cleanup::defer_for(&document, hash, Duration::ZERO) {
+                        remove_elements(&document, hash);
+                    }
+                } else if !transaction::defer_removal(&document, hash)
+                    && !cleanup::defer(&document, hash)
+                {
+                    remove_elements(&document, hash);
+                }
+            });
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ElementMap<'a> {
+    tag: &'a str,
+    attributes: Vec<(&'a str, Cow<'a, str>)>,
+    inner_html: Option<Cow<'a, str>>,
+}
+
+/// An element placed in the head, independent of the render it came from.
+///
+/// Elements with the same tag, attributes and content are equal, whatever the order of their attributes.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedElementMap {
+    pub tag: String,
+    pub attributes: Vec<(String, String)>,
+    pub inner_html: Option<String>,
+}
+
+impl PartialEq for OwnedElementMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_element_map() == other.as_element_map()
+    }
+}
+
+impl Eq for OwnedElementMap {}
+
+impl Hash for OwnedElementMap {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_element_map().hash(state);
+    }
+}
+
+impl OwnedElementMap {
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self {
+            tag: tag.into(),
+            attributes: Vec::new(),
+            inner_html: None,
+        }
+    }
+
+    pub fn attribute(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn inner_html(mut self, inner_html: impl Into<String>) -> Self {
+        self.inner_html = Some(inner_html.into());
+        self
+    }
+
+    /// Injects the element right away, or once the browser is idle, like `"critical"` in rsx.
+    pub fn critical(self, critical: bool) -> Self {
+        self.attribute("data-helmet-critical", critical.to_string())
+    }
+
+    fn as_element_map(&self) -> ElementMap<'_> {
+        ElementMap {
+            tag: &self.tag,
+            attributes: self
+                .attributes
+                .iter()
+                .map(|(name, value)| (name.as_str(), Cow::Borrowed(value.as_str())))
+                .collect(),
+            inner_html: self.inner_html.as_deref().map(Cow::Borrowed),
+        }
+    }
+}
+
+impl From<&ElementMap<'_>> for OwnedElementMap {
+    fn from(element_map: &ElementMap<'_>) -> Self {
+        Self {
+            tag: element_map.tag.to_owned(),
+            attributes: element_map
+                .attributes
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            inner_html: element_map
+                .inner_html
+                .as_ref()
+                .map(|inner_html| inner_html.to_string()),
+        }
+    }
+}
+
+/// Only the content counts, so the same element from another template, e.g. after a hot reload,
+/// or with its attributes in another order, hashes the same.
+impl Hash for ElementMap<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.tag.hash(state);
+        self.sorted_attributes().hash(state);
+        self.inner_html.hash(state);
+    }
+}
 
-lazy_static! {
-    static ref INIT_CACHE: Mutex<Vec<u64>> = Mutex::new(Vec::new());
+impl PartialEq for ElementMap<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.tag == other.tag
+            && self.sorted_attributes() == other.sorted_attributes()
+            && self.inner_html == other.inner_html
+    }
 }
 
-#[derive(Props)]
-pub struct HelmetProps<'a> {
-    #[props(default = 0)]
-    seed: i64,
-    title: Option<String>,
-    children: Element<'a>,
+impl Eq for ElementMap<'_> {}
+
+fn element_hash(seed: i64, element_map: &ElementMap) -> u64 {
+    let mut hasher = FxHasher::default();
+    seed.hash(&mut hasher);
+    element_map.hash(&mut hasher);
+    hasher.finish()
 }
 
-#[allow(non_snake_case)]
-pub fn Helmet<'a>(cx: Scope<'a, HelmetProps<'a>>) -> Element {
-    let document = web_sys::window()?.document()?;
-    let head = document.head()?;
+impl<'a> ElementMap<'a> {
+    fn try_into_element(
+        &self,
+        document: &web_sys::Document,
+        hash: &u64,
+    ) -> Option<web_sys::Element> {
+        if let Ok(new_element) = document.create_element(self.tag) {
+            self.apply_to(&new_element, hash);
 
-    if let Some(title) = cx.props.title.as_deref() {
-        if let Some(node) = head.get_elements_by_tag_name("title").get_with_index(0) {
-            node.set_inner_html(title);
+            Some(new_element)
         } else {
-            let node = document.create_element("title").unwrap();
+            // let key = format!(r#"[data-helmet-id="{hash}"]"#);
 
-            node.set_inner_html(title);
+            // let element = document.query_selector(&key).unwrap()?;
 
-            head.append_child(&node).unwrap();
-        };
+            // Some(element)
+            None
+        }
     }
 
-    let element_maps = extract_element_maps(&cx.props.children)?;
-
-    let Ok(mut init_cache) = INIT_CACHE.try_lock() else {
-        return None;
-    };
+    /// Sets the attributes and content on the element, leaving its other attributes as they are.
+    fn apply_to(&self, element: &web_sys::Element, hash: &u64) {
+        self.attributes.iter().for_each(|(name, value)| {
+            if let Err(error) = element.set_attribute(name, value) {
+                log::warn!(
+                    "dioxus-helmet: left out the attribute `{name}` of <{}>, it is not a valid name: {error:?}",
+                    self.tag
+                );
+            }
+        });
+        let _ = element.set_attribute("data-helmet-id", &hash.to_string());
+        let _ = element.set_attribute("data-helmet-hash", &self.content_hash());
+        let _ = element.set_attribute("data-helmet-version", VERSION);
 
-    element_maps.iter().for_each(|element_map| {
-        let mut hasher = FxHasher::default();
-        cx.props.seed.hash(&mut hasher);
-        element_map.hash(&mut hasher);
-        let hash = hasher.finish();
+        if let Some(key) = self.singleton_key() {
+            let _ = element.set_attribute("data-helmet-key", &key);
+        }
 
-        if !init_cache.contains(&hash) {
-            init_cache.push(hash);
+        if let Some(inner_html) = &self.inner_html {
+            // json is never parsed as html
+            let setter = if self.is_speculation_rules() {
+                ContentSetter::TextContent
+            } else {
+                policy::content_setter(self.tag)
+            };
 
-            if let Some(new_element) = element_map.try_into_element(&document, &hash) {
-                let _ = head.append_child(&new_element);
+            match setter {
+                // for a <template>, this fills its `.content` instead of its children
+                ContentSetter::InnerHtml => element.set_inner_html(inner_html),
+                ContentSetter::TextContent => element.set_text_content(Some(inner_html)),
             }
         }
-    });
+    }
 
-    None
-}
+    fn sorted_attributes(&self) -> Vec<(&str, &str)> {
+        let mut attributes = self
+            .attributes
+            .iter()
+            .map(|(name, value)| (*name, value.as_ref()))
+            .collect::<Vec<_>>();
+        attributes.sort_unstable();
+        attributes
+    }
 
-impl Drop for HelmetProps<'_> {
-    fn drop(&mut self) {
-        let Some(window) = web_sys::window() else {
-            return;
-        };
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_ref())
+    }
 
-        let Some(document) = window.document() else {
-            return;
-        };
+    /// Identifies the content of the element the same way in every version of this crate,
+    /// unlike the `data-helmet-id`, which depends on the seed and the hasher.
+    ///
+    /// This is the 64 bit FNV-1a hash of the tag, the sorted attributes and the content.
+    fn content_hash(&self) -> String {
+        let mut content = vec![self.tag];
+        self.sorted_attributes()
+            .into_iter()
+            .filter(|(name, _)| !name.starts_with("data-helmet-"))
+            .for_each(|(name, value)| content.extend([name, value]));
+        content.extend(self.inner_html.as_deref());
 
-        let Some(element_maps) = extract_element_maps(&self.children) else {
-            return;
-        };
+        let hash = content
+            .iter()
+            .flat_map(|part| part.bytes().chain([0]))
+            .fold(0xcbf29ce484222325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+            });
 
-        let Ok(mut init_cache) = INIT_CACHE.try_lock() else {
-            return;
-        };
+        format!("{hash:016x}")
+    }
 
-        element_maps.iter().for_each(|element_map| {
-            let mut hasher = FxHasher::default();
-            self.seed.hash(&mut hasher);
-            element_map.hash(&mut hasher);
-            let hash = hasher.finish();
+    pub(crate) fn is_charset(&self) -> bool {
+        self.tag == "meta" && self.attribute("charset").is_some()
+    }
 
-            if let Some(index) = init_cache.iter().position(|&c| c == hash) {
-                init_cache.remove(index);
-            }
+    fn is_speculation_rules(&self) -> bool {
+        self.tag == "script"
+            && self
+                .attribute("type")
+                .is_some_and(|kind| kind.eq_ignore_ascii_case("speculationrules"))
+    }
 
-            if let Ok(children) = document.query_selector_all(&format!("[data-helmet-id='{hash}']"))
-            {
-                if let Ok(Some(children_iter)) = js_sys::try_iter(&children) {
-                    children_iter.for_each(|child| {
-                        if let Ok(child) = child {
-                            let el = web_sys::Element::from(child);
-                            el.remove();
-                        };
-                    });
-                }
+    fn is_font_preload(&self) -> bool {
+        self.tag == "link"
+            && self
+                .attribute("rel")
+                .is_some_and(|rel| rel.eq_ignore_ascii_case("preload"))
+            && self
+                .attribute("as")
+                .is_some_and(|as_| as_.eq_ignore_ascii_case("font"))
+    }
+
+    /// Elements of which the head should only ever contain one,
+    /// e.g. `<meta http-equiv="content-type">`, or which were given a `data-helmet-key`.
+    ///
+    /// A new element with the same key replaces the existing one in place.
+    fn singleton_key(&self) -> Option<String> {
+        if let Some(key) = self.attribute("data-helmet-key") {
+            return Some(key.to_owned());
+        }
+
+        // an id is unique within the document, the internal one is `data-helmet-id`
+        if let Some(id) = self.attribute("id") {
+            return Some(format!("{}[id={id}]", self.tag));
+        }
+
+        match self.tag {
+            "meta" => self
+                .attribute("http-equiv")
+                .map(|http_equiv| format!("meta[http-equiv={}]", http_equiv.to_ascii_lowercase())),
+            "base" => Some("base".to_owned()),
+            "script" if self.is_speculation_rules() => {
+                Some("script[type=speculationrules]".to_owned())
             }
-        });
+            "link" => match self.attribute("rel")?.to_ascii_lowercase().as_str() {
+                rel @ ("canonical" | "manifest") => Some(format!("link[rel={rel}]")),
+                // one icon per size
+                rel @ ("icon" | "shortcut icon") => match self.attribute("sizes") {
+                    Some(sizes) => Some(format!("link[rel={rel}][sizes={sizes}]")),
+                    None => Some(format!("link[rel={rel}]")),
+                },
+                _ => None,
+            },
+            _ => None,
+        }
     }
 }
 
-#[derive(Debug, Hash)]
-struct ElementMap<'a> {
-    tag: &'a str,
-    attributes: Vec<(&'a str, &'a str)>,
-    inner_html: Option<&'a str>,
+/// Attributes written with an expression, e.g. `href: "{path}"`.
+///
+/// Attributes without a value, e.g. `integrity: None::<&str>` or an empty string, are left out.
+/// Like the renderer of dioxus, a `false` boolean attribute is left out and a `true` one is
+/// present without a value, e.g. `defer: is_deferred`.
+fn dynamic_attribute<'a>(attribute: &'a Attribute<'a>) -> Option<(&'a str, Cow<'a, str>)> {
+    let value = match &attribute.value {
+        AttributeValue::Text("") | AttributeValue::None => return None,
+        AttributeValue::Text(text) => Cow::Borrowed(*text),
+        AttributeValue::Float(float) => Cow::Owned(float.to_string()),
+        AttributeValue::Int(int) => Cow::Owned(int.to_string()),
+        // `"enabled"` and `"critical"` are read by `extract_vnode`, which tells `false` apart
+        AttributeValue::Bool(bool) if matches!(attribute.name, "enabled" | "critical") => {
+            Cow::Owned(bool.to_string())
+        }
+        AttributeValue::Bool(false) => return None,
+        AttributeValue::Bool(true) => Cow::Borrowed(""),
+        AttributeValue::Listener(_) => {
+            if cfg!(debug_assertions) {
+                log::warn!(
+                    "dioxus-helmet: `{}` is left out, handlers of rsx don't outlive the render of \
+                     Helmet. Give the element a `data-helmet-key` and use `on_event` instead.",
+                    attribute.name
+                );
+            }
+            return None;
+        }
+        AttributeValue::Any(_) => return None,
+    };
+
+    Some((attribute.name, value))
 }
 
-impl<'a> ElementMap<'a> {
-    fn try_into_element(
-        &self,
-        document: &web_sys::Document,
-        hash: &u64,
-    ) -> Option<web_sys::Element> {
-        if let Ok(new_element) = document.create_element(self.tag) {
-            self.attributes.iter().for_each(|(name, value)| {
-                let _ = new_element.set_attribute(name, value);
-            });
-            let _ = new_element.set_attribute("data-helmet-id", &hash.to_string());
+/// The static and dynamic attributes, with repeated ones joined into one,
+/// e.g. `class: "card", class: "{extra}"`. Other than `class` and `style`, the last one wins.
+fn extract_attributes<'a>(
+    vnode: &'a VNode<'_>,
+    attrs: &'a [TemplateAttribute<'_>],
+) -> Vec<(&'a str, Cow<'a, str>)> {
+    let mut attributes: Vec<(&'a str, Cow<'a, str>)> = Vec::with_capacity(attrs.len());
+
+    attrs
+        .iter()
+        .filter_map(|attribute| match attribute {
+            TemplateAttribute::Static { name, value, .. } => Some((*name, Cow::Borrowed(*value))),
+            TemplateAttribute::Dynamic { id } => dynamic_attribute(vnode.dynamic_attrs.get(*id)?),
+        })
+        .for_each(|(name, value)| {
+            // raw identifiers of keywords, e.g. `r#type`, keep their prefix in custom elements
+            let name = name.strip_prefix("r#").unwrap_or(name);
 
-            if let Some(inner_html) = self.inner_html {
-                new_element.set_inner_html(inner_html);
+            match attributes
+                .iter_mut()
+                .find(|(existing, _)| *existing == name)
+            {
+                Some((_, existing)) => match name {
+                    "class" => *existing = Cow::Owned(format!("{existing} {value}")),
+                    "style" => {
+                        *existing =
+                            Cow::Owned(format!("{}; {value}", existing.trim_end_matches(';')))
+                    }
+                    _ => *existing = value,
+                },
+                None => attributes.push((name, value)),
             }
+        });
 
-            Some(new_element)
-        } else {
-            // let key = format!(r#"[data-helmet-id="{hash}"]"#);
+    attributes
+}
 
-            // let element = document.query_selector(&key).unwrap()?;
+/// Joins the static and dynamic text children, e.g. of `style { "body {{ color: " "{color}" " }}" }`.
+fn extract_text<'a>(
+    vnode: &'a VNode<'_>,
+    children: &'a [TemplateNode<'_>],
+) -> Option<Cow<'a, str>> {
+    let mut texts = children.iter().filter_map(|child| match child {
+        TemplateNode::Text { text } => Some(*text),
+        TemplateNode::DynamicText { id } => match vnode.dynamic_nodes.get(*id)? {
+            DynamicNode::Text(text) => Some(text.value),
+            _ => None,
+        },
+        _ => None,
+    });
 
-            // Some(element)
-            None
+    let first = texts.next()?;
+
+    match texts.next() {
+        Some(second) => {
+            let mut text = [first, second].concat();
+            texts.for_each(|rest| text.push_str(rest));
+            Some(Cow::Owned(text))
         }
+        None => Some(Cow::Borrowed(first)),
     }
 }
 
-fn extract_element_maps<'a>(children: &'a Element) -> Option<Vec<ElementMap<'a>>> {
-    if let Some(vnode) = &children {
-        let elements = vnode
-            .template
-            .get()
-            .roots
-            .iter()
-            .filter_map(|child| {
-                if let TemplateNode::Element {
-                    tag,
-                    attrs,
-                    children,
-                    ..
-                } = child
-                {
-                    let attributes = attrs
-                        .iter()
-                        .filter_map(|attribute| match attribute {
-                            TemplateAttribute::Static { name, value, .. } => Some((*name, *value)),
-                            TemplateAttribute::Dynamic { .. } => None,
-                        })
-                        .collect();
-
-                    let inner_html = match children.first() {
-                        Some(TemplateNode::Text { text }) => Some(*text),
-                        Some(TemplateNode::Element { children, .. }) if children.len() == 1 => {
-                            match children.first() {
-                                Some(TemplateNode::Text { text }) => Some(*text),
-                                _ => None,
-                            }
-                        }
-                        _ => None,
-                    };
-
-                    Some(ElementMap {
-                        tag,
-                        attributes,
-                        inner_html,
-                    })
-                } else {
-                    None
-                }
-            })
-            .collect();
+/// Serializes the children as html, e.g. of `template { div { class: "card" } }`.
+fn extract_html(vnode: &VNode<'_>, children: &[TemplateNode<'_>], html: &mut String) {
+    children.iter().for_each(|child| match child {
+        TemplateNode::Element {
+            tag,
+            attrs,
+            children,
+            ..
+        } => {
+            html.push('<');
+            html.push_str(tag);
 
-        Some(elements)
-    } else {
-        None
+            extract_attributes(vnode, attrs)
+                .into_iter()
+                .for_each(|(name, value)| {
+                    let _ = write!(html, r#" {name}="{}""#, ssr::escape(&value));
+                });
+
+            html.push('>');
+
+            if !ssr::is_void(tag) {
+                extract_html(vnode, children, html);
+                let _ = write!(html, "</{tag}>");
+            }
+        }
+        // text stays text, whether written in rsx or coming from a value
+        TemplateNode::Text { text } => html.push_str(&ssr::escape(text)),
+        TemplateNode::DynamicText { id } | TemplateNode::Dynamic { id } => {
+            match vnode.dynamic_nodes.get(*id) {
+                Some(DynamicNode::Text(text)) => html.push_str(&ssr::escape(text.value)),
+                Some(DynamicNode::Fragment(vnodes)) => vnodes
+                    .iter()
+                    .for_each(|vnode| extract_html(vnode, vnode.template.get().roots, html)),
+                _ => {}
+            }
+        }
+    });
+}
+
+/// The elements among the children, none without children, e.g. for a Helmet with only a `title`.
+fn extract_element_maps<'a>(children: &'a Element) -> Vec<ElementMap<'a>> {
+    let mut elements = Vec::new();
+
+    if let Some(vnode) = &children {
+        extract_vnode(vnode, &mut elements);
     }
 
+    elements
+
     // if let Some(VNode::Fragment(fragment)) = &children {
     //     let elements = fragment
     //         .children
@@ -276,3 +1609,425 @@ fn extract_element_maps<'a>(children: &'a Element) -> Option<Vec<ElementMap<'a>>
     //     None
     // }
 }
+
+/// Collects the elements among the roots of the node, following dynamic lists, e.g. from `for` loops.
+fn extract_vnode<'a>(vnode: &'a VNode<'_>, elements: &mut Vec<ElementMap<'a>>) {
+    vnode
+        .template
+        .get()
+        .roots
+        .iter()
+        .for_each(|child| match child {
+            TemplateNode::Element {
+                tag,
+                attrs,
+                children,
+                ..
+            } => {
+                let mut attributes = extract_attributes(vnode, attrs);
+
+                // a toggle for the element, never injected itself
+                if let Some(position) = attributes.iter().position(|(name, _)| *name == "enabled") {
+                    if attributes.remove(position).1 == "false" {
+                        return;
+                    }
+                }
+
+                // when to inject the element, see `Helmet`
+                if let Some((name, _)) = attributes.iter_mut().find(|(name, _)| *name == "critical") {
+                    *name = "data-helmet-critical";
+                }
+
+                let inner_html = match children.first() {
+                    // the children of a template are its content, so they stay elements
+                    _ if *tag == "template" => {
+                        let mut html = String::new();
+                        extract_html(vnode, children, &mut html);
+                        Some(Cow::Owned(html))
+                    }
+                    Some(TemplateNode::Element { children, .. }) if children.len() == 1 => {
+                        match children.first() {
+                            Some(TemplateNode::Text { text }) => Some(Cow::Borrowed(*text)),
+                            _ => None,
+                        }
+                    }
+                    _ => extract_text(vnode, children),
+                };
+
+                elements.push(ElementMap {
+                    tag,
+                    attributes,
+                    inner_html,
+                });
+            }
+            TemplateNode::Dynamic { id } => match vnode.dynamic_nodes.get(*id) {
+                Some(DynamicNode::Fragment(vnodes)) => vnodes
+                    .iter()
+                    .for_each(|vnode| extract_vnode(vnode, elements)),
+                Some(DynamicNode::Component(component)) if cfg!(debug_assertions) => {
+                    log::warn!(
+                        "dioxus-helmet: the children of Helmet are never rendered, so <{} /> is left out. \
+                         Use a function returning `rsx!` instead of a component.",
+                        component.name
+                    );
+                }
+                _ => {}
+            },
+            _ => {}
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Renders the app once and flushes the head of the [`HeadCollector`] it provides.
+    pub(crate) fn render_head(app: fn(Scope) -> Element) -> String {
+        let mut dom = VirtualDom::new(app);
+        let _ = dom.rebuild();
+
+        dom.base_scope()
+            .consume_context::<HeadCollector>()
+            .expect("the app provides a HeadCollector")
+            .flush_head()
+    }
+
+    #[test]
+    fn http_equiv_is_a_singleton_whatever_its_case() {
+        let lower = OwnedElementMap::new("meta")
+            .attribute("http-equiv", "content-type")
+            .attribute("content", "text/html");
+        let upper = OwnedElementMap::new("meta")
+            .attribute("http-equiv", "Content-Type")
+            .attribute("content", "text/html; charset=utf-8");
+
+        assert_eq!(
+            lower.as_element_map().singleton_key().as_deref(),
+            Some("meta[http-equiv=content-type]")
+        );
+        assert_eq!(
+            lower.as_element_map().singleton_key(),
+            upper.as_element_map().singleton_key()
+        );
+    }
+
+    #[test]
+    fn different_http_equivs_are_kept_apart() {
+        let content_type = OwnedElementMap::new("meta").attribute("http-equiv", "content-type");
+        let csp = OwnedElementMap::new("meta").attribute("http-equiv", "content-security-policy");
+
+        assert_ne!(
+            content_type.as_element_map().singleton_key(),
+            csp.as_element_map().singleton_key()
+        );
+    }
+
+    #[test]
+    fn the_last_http_equiv_of_a_kind_is_collected() {
+        #[allow(non_snake_case)]
+        fn App(cx: Scope) -> Element {
+            cx.provide_context(HeadCollector::new());
+
+            cx.render(rsx! {
+                Helmet {
+                    meta { "http-equiv": "content-type", content: "text/html" }
+                    meta { "http-equiv": "content-security-policy", content: "default-src 'self'" }
+                }
+                Helmet {
+                    meta { "http-equiv": "Content-Type", content: "text/html; charset=utf-8" }
+                }
+            })
+        }
+
+        let head = render_head(App);
+
+        assert_eq!(head.matches("http-equiv=\"content-type\"").count(), 0);
+        assert_eq!(head.matches("http-equiv=\"Content-Type\"").count(), 1);
+        assert!(head.contains("content=\"text/html; charset=utf-8\""));
+        assert!(head.contains("http-equiv=\"content-security-policy\""));
+    }
+
+    #[test]
+    fn modern_resource_hints_pass_through() {
+        #[allow(non_snake_case)]
+        fn App(cx: Scope) -> Element {
+            cx.provide_context(HeadCollector::new());
+
+            cx.render(rsx! {
+                Helmet {
+                    link { rel: "preload", href: "/hero.avif", "as": "image", "fetchpriority": "high" }
+                    link { rel: "stylesheet", href: "/critical.css", "blocking": "render" }
+                }
+            })
+        }
+
+        let head = render_head(App);
+
+        assert!(head.contains(r#"fetchpriority="high""#));
+        assert!(head.contains(r#"blocking="render""#));
+    }
+
+    #[test]
+    fn alternate_stylesheets_with_other_titles_are_distinct() {
+        let themes = HeadBuilder::new()
+            .alternate_stylesheet("Dark", "/dark.css")
+            .alternate_stylesheet("Sepia", "/sepia.css")
+            .build();
+        let (dark, sepia) = (themes[0].as_element_map(), themes[1].as_element_map());
+
+        assert_eq!(dark.attribute("rel"), Some("alternate stylesheet"));
+        assert_eq!(dark.singleton_key(), None);
+        assert_ne!(element_hash(0, &dark), element_hash(0, &sepia));
+    }
+
+    #[test]
+    fn every_theme_is_collected_once() {
+        #[allow(non_snake_case)]
+        fn App(cx: Scope) -> Element {
+            cx.provide_context(HeadCollector::new());
+
+            cx.render(rsx! {
+                Helmet {
+                    elements: HeadBuilder::new()
+                        .alternate_stylesheet("Dark", "/dark.css")
+                        .alternate_stylesheet("Sepia", "/sepia.css")
+                        .build(),
+                }
+                Helmet {
+                    link { rel: "alternate stylesheet", title: "Dark", href: "/dark.css" }
+                }
+            })
+        }
+
+        let head = render_head(App);
+
+        assert_eq!(head.matches(r#"title="Dark""#).count(), 1);
+        assert_eq!(head.matches(r#"title="Sepia""#).count(), 1);
+    }
+
+    #[test]
+    fn unicode_content_hashes_differ() {
+        let tokyo = OwnedElementMap::new("meta")
+            .attribute("name", "description")
+            .attribute("content", "東京");
+        let kyoto = OwnedElementMap::new("meta")
+            .attribute("name", "description")
+            .attribute("content", "京都");
+
+        let hash = tokyo.as_element_map().content_hash();
+
+        assert_eq!(hash.len(), 16);
+        assert_ne!(hash, kyoto.as_element_map().content_hash());
+    }
+
+    #[test]
+    fn unicode_titles_and_descriptions_are_collected_whole() {
+        #[allow(non_snake_case)]
+        fn App(cx: Scope) -> Element {
+            cx.provide_context(HeadCollector::new());
+
+            cx.render(rsx! {
+                Helmet {
+                    title { "ラーメン 🍜" }
+                    meta { name: "description", content: "美味しい 🍥" }
+                }
+            })
+        }
+
+        let head = render_head(App);
+
+        assert!(head.starts_with("<title>ラーメン 🍜</title>"));
+        assert!(head.contains(r#"content="美味しい 🍥""#));
+    }
+
+    #[test]
+    fn boolean_attributes_are_present_or_left_out() {
+        #[allow(non_snake_case)]
+        fn App(cx: Scope) -> Element {
+            cx.provide_context(HeadCollector::new());
+            let (deferred, asynchronous) = (true, false);
+
+            cx.render(rsx! {
+                Helmet {
+                    script { src: "/app.js", defer: deferred, "async": asynchronous }
+                    meta { "enabled": asynchronous, name: "robots", content: "noindex" }
+                }
+            })
+        }
+
+        let head = render_head(App);
+
+        assert!(head.starts_with(r#"<script src="/app.js" defer="""#));
+        assert!(!head.contains("async"));
+        assert!(!head.contains("robots"));
+    }
+
+    #[test]
+    fn text_of_a_template_is_escaped() {
+        #[allow(non_snake_case)]
+        fn App(cx: Scope) -> Element {
+            cx.provide_context(HeadCollector::new());
+            let user = "<img src=x onerror=alert(1)>";
+
+            cx.render(rsx! {
+                Helmet {
+                    template { id: "row", p { "1 < 2 & " "{user}" } }
+                }
+            })
+        }
+
+        let head = render_head(App);
+
+        assert!(head.contains("<p>1 &lt; 2 &amp; &lt;img src=x onerror=alert(1)&gt;</p>"));
+    }
+
+    #[test]
+    fn the_charset_comes_before_the_title() {
+        #[allow(non_snake_case)]
+        fn App(cx: Scope) -> Element {
+            cx.provide_context(HeadCollector::new());
+
+            cx.render(rsx! {
+                Helmet {
+                    title: "Helmet".to_owned(),
+                    meta { name: "description", content: "Helmet" }
+                    meta { charset: "utf-8" }
+                }
+            })
+        }
+
+        let head = render_head(App);
+
+        assert!(head.starts_with("<meta charset=\"utf-8\""), "{head}");
+        assert!(head.contains("<title>Helmet</title>"));
+        assert!(head.find("<title>") < head.find("name=\"description\""));
+    }
+
+    #[test]
+    fn the_last_title_child_is_the_title() {
+        #[allow(non_snake_case)]
+        fn App(cx: Scope) -> Element {
+            cx.provide_context(HeadCollector::new());
+
+            cx.render(rsx! {
+                Helmet {
+                    title { "First" }
+                    meta { name: "description", content: "Helmet" }
+                    title { "Last" }
+                }
+            })
+        }
+
+        let head = render_head(App);
+
+        assert_eq!(head.matches("<title>").count(), 1);
+        assert!(head.starts_with("<title>Last</title>"), "{head}");
+    }
+
+    #[test]
+    fn static_and_dynamic_text_make_up_the_content() {
+        #[allow(non_snake_case)]
+        fn App(cx: Scope) -> Element {
+            cx.provide_context(HeadCollector::new());
+            let color = "red";
+
+            cx.render(rsx! {
+                Helmet {
+                    style { "a{{color:" "{color}" "}}" }
+                }
+            })
+        }
+
+        let head = render_head(App);
+
+        assert!(head.ends_with("a{color:red}</style>"), "{head}");
+    }
+
+    #[test]
+    fn every_element_of_a_for_loop_is_collected() {
+        #[allow(non_snake_case)]
+        fn App(cx: Scope) -> Element {
+            cx.provide_context(HeadCollector::new());
+            let authors = ["Saicu", "syrflover", "Ferris"];
+
+            cx.render(rsx! {
+                Helmet {
+                    for author in authors {
+                        meta { name: "author", content: "{author}" }
+                    }
+                }
+            })
+        }
+
+        let head = render_head(App);
+
+        assert_eq!(head.matches(r#"name="author""#).count(), 3);
+        assert!(head.contains(r#"content="Ferris""#));
+    }
+
+    #[test]
+    fn enabled_toggles_an_element_without_being_injected() {
+        #[allow(non_snake_case)]
+        fn App(cx: Scope) -> Element {
+            cx.provide_context(HeadCollector::new());
+            let preview = true;
+
+            cx.render(rsx! {
+                Helmet {
+                    meta { "enabled": preview, name: "robots", content: "noindex" }
+                    meta { "enabled": "false", name: "googlebot", content: "noindex" }
+                }
+            })
+        }
+
+        let head = render_head(App);
+
+        assert!(head.contains(r#"name="robots""#));
+        assert!(!head.contains("googlebot"));
+        assert!(!head.contains("enabled"));
+    }
+
+    #[test]
+    fn keyword_attributes_keep_their_html_names() {
+        #[allow(non_snake_case)]
+        fn App(cx: Scope) -> Element {
+            cx.provide_context(HeadCollector::new());
+
+            cx.render(rsx! {
+                Helmet {
+                    script { r#type: "module", src: "/app.js" }
+                    link { rel: "preload", href: "/font.woff2", r#type: "font/woff2" }
+                    helmet-slot { r#for: "header" }
+                }
+            })
+        }
+
+        let head = render_head(App);
+
+        assert!(head.contains(r#"type="module""#), "{head}");
+        assert!(head.contains(r#"type="font/woff2""#), "{head}");
+        assert!(head.contains(r#"for="header""#), "{head}");
+        assert!(!head.contains("r#"));
+    }
+
+    #[test]
+    fn repeated_classes_and_styles_are_joined() {
+        #[allow(non_snake_case)]
+        fn App(cx: Scope) -> Element {
+            cx.provide_context(HeadCollector::new());
+            let extra = "print";
+
+            cx.render(rsx! {
+                Helmet {
+                    link { rel: "stylesheet", href: "/a.css", class: "theme", class: "{extra}" }
+                    style { style: "color: red;", style: "margin: 0", "a{{}}" }
+                }
+            })
+        }
+
+        let head = render_head(App);
+
+        assert!(head.contains(r#"class="theme print""#), "{head}");
+        assert!(head.contains(r#"style="color: red; margin: 0""#), "{head}");
+    }
+}
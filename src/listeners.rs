@@ -0,0 +1,92 @@
+use rustc_hash::FxHashMap;
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast};
+
+type Listener = Rc<RefCell<dyn FnMut(&web_sys::Event)>>;
+type Attached = (web_sys::Element, String, Closure<dyn FnMut(web_sys::Event)>);
+
+thread_local! {
+    /// The listeners for the events of each `data-helmet-key`.
+    static LISTENERS: RefCell<FxHashMap<String, Vec<(String, Listener)>>> =
+        RefCell::new(FxHashMap::default());
+    /// The listeners attached to the injected elements of each hash.
+    static ATTACHED: RefCell<FxHashMap<u64, Vec<Attached>>> = RefCell::new(FxHashMap::default());
+}
+
+/// Listens to an event of the elements with the given `data-helmet-key`, e.g. the `load` of a script.
+///
+/// ```rust
+/// on_event("analytics", "load", |_| init_analytics());
+///
+/// rsx! {
+///     Helmet {
+///         script { "data-helmet-key": "analytics", src: "https://example.com/analytics.js" }
+///     }
+/// }
+/// ```
+///
+/// Handlers like `onload` in rsx can't outlive the render of the Helmet, so they are left out of
+/// the injected elements. The listener is attached whenever such an element gets injected and
+/// detached once it is removed.
+pub fn on_event(
+    key: impl Into<String>,
+    event: impl Into<String>,
+    callback: impl FnMut(&web_sys::Event) + 'static,
+) {
+    LISTENERS.with(|listeners| {
+        listeners
+            .borrow_mut()
+            .entry(key.into())
+            .or_default()
+            .push((event.into(), Rc::new(RefCell::new(callback))));
+    });
+}
+
+/// Attaches the listeners for the key of the element, if it has one.
+pub(crate) fn attach(element: &web_sys::Element, hash: u64) {
+    let Some(key) = element.get_attribute("data-helmet-key") else {
+        return;
+    };
+
+    let listeners =
+        LISTENERS.with(|listeners| listeners.borrow().get(&key).cloned().unwrap_or_default());
+
+    if listeners.is_empty() {
+        return;
+    }
+
+    // attached again, e.g. when the element was adopted or merged
+    detach(hash);
+
+    let attached = listeners
+        .into_iter()
+        .filter_map(|(event, listener)| {
+            let closure =
+                Closure::<dyn FnMut(web_sys::Event)>::new(move |event: web_sys::Event| {
+                    if let Ok(mut listener) = listener.try_borrow_mut() {
+                        listener(&event);
+                    }
+                });
+
+            element
+                .add_event_listener_with_callback(&event, closure.as_ref().unchecked_ref())
+                .ok()?;
+
+            Some((element.clone(), event, closure))
+        })
+        .collect::<Vec<_>>();
+
+    ATTACHED.with(|all| all.borrow_mut().insert(hash, attached));
+}
+
+/// Detaches the listeners of the elements of the hash.
+pub(crate) fn detach(hash: u64) {
+    let Some(attached) = ATTACHED.with(|all| all.borrow_mut().remove(&hash)) else {
+        return;
+    };
+
+    attached.iter().for_each(|(element, event, closure)| {
+        let _ =
+            element.remove_event_listener_with_callback(event, closure.as_ref().unchecked_ref());
+    });
+}
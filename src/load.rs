@@ -0,0 +1,248 @@
+use crate::{
+    builder::font_preload, document, inject_owned, lock_init_cache, remove_elements,
+    OwnedElementMap, Placement, Rel,
+};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+
+/// Injects a stylesheet link and resolves once it has loaded,
+/// e.g. to hold back UI which depends on it.
+///
+/// ```rust
+/// inject_stylesheet("/theme.css").await?;
+/// ```
+///
+/// Errors with the `error` event if the stylesheet fails to load.
+pub async fn inject_stylesheet(href: impl Into<String>) -> Result<(), JsValue> {
+    let element = OwnedElementMap::new("link")
+        .attribute("rel", "stylesheet")
+        .attribute("href", href);
+
+    let Some((_, element)) = inject_owned(0, &element, Placement::default()) else {
+        return Err(no_document());
+    };
+
+    // already injected and loaded before
+    if let Some(link) = element.dyn_ref::<web_sys::HtmlLinkElement>() {
+        if link.sheet().is_some() {
+            return Ok(());
+        }
+    }
+
+    loaded(&element).await
+}
+
+/// Injects a stylesheet link and, if it fails to load, e.g. with a 404, replaces it with
+/// a link to the fallback.
+///
+/// ```rust
+/// inject_stylesheet_with_fallback("https://cdn.example.com/style.css", "/style.css");
+/// ```
+pub fn inject_stylesheet_with_fallback(href: impl Into<String>, fallback: impl Into<String>) {
+    let element = OwnedElementMap::new("link")
+        .attribute("rel", "stylesheet")
+        .attribute("href", href);
+    let fallback = fallback.into();
+
+    let Some((hash, element)) = inject_owned(0, &element, Placement::default()) else {
+        return;
+    };
+
+    // already injected and loaded before
+    if let Some(link) = element.dyn_ref::<web_sys::HtmlLinkElement>() {
+        if link.sheet().is_some() {
+            return;
+        }
+    }
+
+    spawn_local(async move {
+        if loaded(&element).await.is_ok() {
+            return;
+        }
+
+        log::warn!("dioxus-helmet: a stylesheet failed to load, injecting {fallback} instead");
+
+        if let Some(mut init_cache) = lock_init_cache() {
+            init_cache.retain(|&c| c != hash);
+        }
+
+        if let Some(document) = document() {
+            remove_elements(&document, hash);
+        }
+
+        let _ = inject_stylesheet(fallback).await;
+    });
+}
+
+/// Injects an external script and resolves once it has loaded, e.g. to call into its globals.
+///
+/// ```rust
+/// inject_script("https://example.com/analytics.js").await?;
+/// ```
+///
+/// Errors with the `error` event if the script fails to load.
+pub async fn inject_script(src: impl Into<String>) -> Result<(), JsValue> {
+    let element = OwnedElementMap::new("script").attribute("src", src);
+
+    let Some((_, element)) = inject_owned(0, &element, Placement::default()) else {
+        return Err(no_document());
+    };
+
+    // scripts don't tell whether they were loaded before, it is recorded on the element when injected
+    loaded(&element).await
+}
+
+/// Injects a preload for a font, see [`HeadBuilder::preload_font`](crate::HeadBuilder::preload_font).
+pub fn preload_font(href: impl Into<String>) {
+    inject_owned(0, &font_preload(href.into()), Placement::default());
+}
+
+/// Injects a prefetch for a resource which is likely needed soon, e.g. from `onmouseenter` of a link.
+///
+/// ```rust
+/// rsx! {
+///     a { href: "/about", onmouseenter: |_| prefetch("/about"), "About" }
+/// }
+/// ```
+///
+/// Prefetching the same resource again has no effect.
+pub fn prefetch(href: impl Into<String>) {
+    let element = OwnedElementMap::new("link")
+        .attribute("rel", Rel::Prefetch.as_str())
+        .attribute("href", href);
+
+    inject_owned(0, &element, Placement::default());
+}
+
+/// The error without a document, e.g. in a native test of the app.
+///
+/// Off wasm, creating a `JsValue` aborts the process, only the reserved ones are plain constants.
+fn no_document() -> JsValue {
+    #[cfg(target_arch = "wasm32")]
+    {
+        JsValue::from_str("dioxus-helmet: no document to inject into")
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        JsValue::UNDEFINED
+    }
+}
+
+/// Resolves on the `load` and rejects on the `error` event of the element.
+///
+/// The outcome of scripts and stylesheets is recorded on them as soon as they are injected, see
+/// [`record`], so waiting on one which loaded or failed before resolves or rejects right away.
+pub(crate) async fn loaded(element: &web_sys::Element) -> Result<(), JsValue> {
+    if element.has_attribute("data-helmet-loaded") {
+        return Ok(());
+    }
+    if element.has_attribute("data-helmet-failed") {
+        return Err(JsValue::from_str(
+            "dioxus-helmet: the element failed to load before",
+        ));
+    }
+
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        let _ = element.add_event_listener_with_callback("load", &resolve);
+        let _ = element.add_event_listener_with_callback("error", &reject);
+    });
+
+    JsFuture::from(promise).await.map(|_| ())
+}
+
+thread_local! {
+    /// Marks the target of a `load` or `error` event, shared by every recorded element.
+    static RECORDER: Closure<dyn FnMut(web_sys::Event)> =
+        Closure::new(|event: web_sys::Event| {
+            let Some(element) = event
+                .target()
+                .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+            else {
+                return;
+            };
+
+            let marker = if event.type_() == "load" {
+                "data-helmet-loaded"
+            } else {
+                "data-helmet-failed"
+            };
+            let _ = element.set_attribute(marker, "");
+        });
+}
+
+/// Records whether an injected `script[src]` or `link[rel=stylesheet]` loaded or failed,
+/// for [`loaded`] to tell later.
+pub(crate) fn record(element: &web_sys::Element) {
+    if !is_loading(element) {
+        return;
+    }
+
+    RECORDER.with(|recorder| {
+        let _ = element.add_event_listener_with_callback("load", recorder.as_ref().unchecked_ref());
+        let _ =
+            element.add_event_listener_with_callback("error", recorder.as_ref().unchecked_ref());
+    });
+}
+
+/// Like [`record`], for an element rendered on the server, which may be done already.
+///
+/// A stylesheet tells by its sheet, a script only by the document having finished loading.
+pub(crate) fn record_adopted(document: &web_sys::Document, element: &web_sys::Element) {
+    if !is_loading(element) {
+        return;
+    }
+
+    let sheet = element
+        .dyn_ref::<web_sys::HtmlLinkElement>()
+        .map(|link| link.sheet().is_some());
+
+    if sheet == Some(true) {
+        let _ = element.set_attribute("data-helmet-loaded", "");
+    } else if document.ready_state() == "complete" {
+        let marker = match sheet {
+            Some(_) => "data-helmet-failed",
+            None => "data-helmet-loaded",
+        };
+        let _ = element.set_attribute(marker, "");
+    }
+
+    record(element);
+}
+
+fn is_loading(element: &web_sys::Element) -> bool {
+    match element.tag_name().to_ascii_lowercase().as_str() {
+        "script" => element.has_attribute("src"),
+        "link" => element.get_attribute("rel").is_some_and(|rel| {
+            rel.split_ascii_whitespace()
+                .any(|rel| rel.eq_ignore_ascii_case("stylesheet"))
+        }),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        future::Future,
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    fn poll_once<T>(future: impl Future<Output = T>) -> Poll<T> {
+        pin!(future).poll(&mut Context::from_waker(Waker::noop()))
+    }
+
+    #[test]
+    fn loading_without_a_document_errors_instead_of_aborting() {
+        assert!(matches!(
+            poll_once(inject_stylesheet("/theme.css")),
+            Poll::Ready(Err(_))
+        ));
+        assert!(matches!(
+            poll_once(inject_script("/app.js")),
+            Poll::Ready(Err(_))
+        ));
+    }
+}
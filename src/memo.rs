@@ -0,0 +1,61 @@
+use rustc_hash::FxHashMap;
+use std::cell::RefCell;
+
+thread_local! {
+    /// The hashes each Helmet instance claimed in its last render, for its drop to release.
+    static CLAIMED: RefCell<FxHashMap<usize, Vec<u64>>> = RefCell::new(FxHashMap::default());
+}
+
+/// The hashes of the elements of a Helmet, with the version and seed they were computed for.
+///
+/// Kept by the scope of the Helmet, as its props are created again on every render of the parent.
+#[derive(Default)]
+pub(crate) struct Memo {
+    version: u64,
+    seed: i64,
+    hashes: Option<Vec<u64>>,
+}
+
+impl Memo {
+    /// The hashes of the elements, only computed again when the version or seed changed.
+    ///
+    /// Without a version, they are computed on every call.
+    pub(crate) fn hashes(
+        &mut self,
+        version: Option<u64>,
+        seed: i64,
+        compute: impl FnOnce() -> Vec<u64>,
+    ) -> Vec<u64> {
+        let Some(version) = version else {
+            return compute();
+        };
+
+        if let Some(hashes) = self
+            .hashes
+            .as_ref()
+            .filter(|_| self.version == version && self.seed == seed)
+        {
+            return hashes.clone();
+        }
+
+        let hashes = compute();
+
+        self.version = version;
+        self.seed = seed;
+        self.hashes = Some(hashes.clone());
+
+        hashes
+    }
+}
+
+/// Remembers the hashes the instance claimed, which may be memoized ones of a previous render.
+pub(crate) fn remember(instance: usize, hashes: &[u64]) {
+    CLAIMED.with(|claimed| {
+        claimed.borrow_mut().insert(instance, hashes.to_vec());
+    });
+}
+
+#[cfg(not(feature = "ssr-only"))]
+pub(crate) fn forget(instance: usize) -> Option<Vec<u64>> {
+    CLAIMED.with(|claimed| claimed.borrow_mut().remove(&instance))
+}
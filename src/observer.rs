@@ -0,0 +1,72 @@
+use crate::{document, lock_init_cache};
+use std::cell::RefCell;
+use wasm_bindgen::{closure::Closure, JsCast};
+
+thread_local! {
+    static OBSERVER: RefCell<Option<web_sys::MutationObserver>> = const { RefCell::new(None) };
+}
+
+/// Watches the head for elements of Helmet which other code removes, e.g. third-party scripts,
+/// and forgets they were injected, so the next render of their Helmet injects them again.
+///
+/// ```rust
+/// dioxus_helmet::observe_head();
+/// dioxus_web::launch(App);
+/// ```
+///
+/// Calling it again has no effect.
+pub fn observe_head() {
+    if OBSERVER.with(|observer| observer.borrow().is_some()) {
+        return;
+    }
+
+    let Some(document) = document() else {
+        return;
+    };
+    let Some(head) = document.head() else {
+        return;
+    };
+
+    let callback = Closure::<dyn FnMut(js_sys::Array)>::new(move |records: js_sys::Array| {
+        let hashes = records
+            .iter()
+            .filter_map(|record| record.dyn_into::<web_sys::MutationRecord>().ok())
+            .flat_map(|record| {
+                let removed = record.removed_nodes();
+                (0..removed.length()).filter_map(move |index| removed.item(index))
+            })
+            .filter_map(|node| node.dyn_into::<web_sys::Element>().ok())
+            .filter_map(|el| el.get_attribute("data-helmet-id"))
+            // replaced by a newer version of itself, e.g. after `invalidate`
+            .filter(|id| {
+                document
+                    .query_selector(&format!("[data-helmet-id='{id}']"))
+                    .ok()
+                    .flatten()
+                    .is_none()
+            })
+            .filter_map(|id| id.parse::<u64>().ok())
+            .collect::<Vec<_>>();
+
+        if hashes.is_empty() {
+            return;
+        }
+
+        if let Some(mut init_cache) = lock_init_cache() {
+            init_cache.retain(|hash| !hashes.contains(hash));
+        }
+    });
+
+    let Ok(observer) = web_sys::MutationObserver::new(callback.as_ref().unchecked_ref()) else {
+        return;
+    };
+
+    let options = web_sys::MutationObserverInit::new();
+    options.set_child_list(true);
+
+    if observer.observe_with_options(&head, &options).is_ok() {
+        // lives as long as the observer, which is never disconnected
+        callback.forget();
+        OBSERVER.with(|slot| *slot.borrow_mut() = Some(observer));
+    }
+}
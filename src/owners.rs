@@ -0,0 +1,52 @@
+use rustc_hash::FxHashMap;
+use std::cell::RefCell;
+
+thread_local! {
+    /// The Helmet instances which injected an element, in the order they did.
+    static OWNERS: RefCell<FxHashMap<u64, Vec<usize>>> = RefCell::new(FxHashMap::default());
+}
+
+/// Records the instance as an owner of the element.
+///
+/// Returns the first other owner if the instance just joined it.
+pub(crate) fn claim(hash: u64, instance: usize) -> Option<usize> {
+    OWNERS.with(|owners| {
+        let mut owners = owners.borrow_mut();
+        let instances = owners.entry(hash).or_default();
+
+        if instances.contains(&instance) {
+            return None;
+        }
+
+        instances.push(instance);
+        instances
+            .first()
+            .copied()
+            .filter(|&owner| owner != instance)
+    })
+}
+
+/// Forgets the instance as an owner of the element, returns whether no owner is left.
+pub(crate) fn release(hash: u64, instance: usize) -> bool {
+    OWNERS.with(|owners| {
+        let mut owners = owners.borrow_mut();
+
+        let Some(instances) = owners.get_mut(&hash) else {
+            return true;
+        };
+
+        instances.retain(|&owner| owner != instance);
+
+        if instances.is_empty() {
+            owners.remove(&hash);
+            true
+        } else {
+            false
+        }
+    })
+}
+
+#[cfg(feature = "test-utils")]
+pub(crate) fn clear() {
+    OWNERS.with(|owners| owners.borrow_mut().clear());
+}
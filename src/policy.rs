@@ -0,0 +1,61 @@
+use lazy_static::lazy_static;
+use rustc_hash::FxHashMap;
+use std::sync::Mutex;
+
+lazy_static! {
+    static ref DUPLICATE_POLICY: Mutex<DuplicatePolicy> = Mutex::new(DuplicatePolicy::default());
+    static ref CONTENT_POLICY: Mutex<FxHashMap<String, ContentSetter>> =
+        Mutex::new(FxHashMap::default());
+}
+
+/// What happens when an element which is already in the head gets injected again.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Keep the existing element.
+    #[default]
+    Skip,
+    /// Keep the existing element and log a warning.
+    Warn,
+    /// Replace the existing element with a new one.
+    Replace,
+}
+
+pub fn set_duplicate_policy(policy: DuplicatePolicy) {
+    *DUPLICATE_POLICY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = policy;
+}
+
+pub(crate) fn duplicate_policy() -> DuplicatePolicy {
+    *DUPLICATE_POLICY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// How the content of an element gets set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContentSetter {
+    /// Parse the content as html, with `set_inner_html`.
+    #[default]
+    InnerHtml,
+    /// Set the content as it is, with `set_text_content`.
+    TextContent,
+}
+
+/// Sets the content of every element with the given tag with the setter, e.g. to set
+/// `<style>` with [`ContentSetter::TextContent`]. Tags without one use [`ContentSetter::InnerHtml`].
+pub fn set_content_policy(tag: impl Into<String>, setter: ContentSetter) {
+    CONTENT_POLICY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(tag.into().to_ascii_lowercase(), setter);
+}
+
+pub(crate) fn content_setter(tag: &str) -> ContentSetter {
+    CONTENT_POLICY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(&tag.to_ascii_lowercase())
+        .copied()
+        .unwrap_or_default()
+}
@@ -0,0 +1,204 @@
+use crate::{ssr::escape, ElementMap};
+use std::borrow::Cow;
+
+/// The attributes allowed on every tag, besides `data-*`.
+const GLOBAL_ATTRIBUTES: &[&str] = &["id", "class", "lang", "dir"];
+
+/// The `http-equiv`s which can't navigate away or set cookies.
+const HTTP_EQUIVS: &[&str] = &[
+    "content-type",
+    "content-language",
+    "content-security-policy",
+    "default-style",
+    "x-ua-compatible",
+];
+
+/// The attributes which point to a url.
+const URL_ATTRIBUTES: &[&str] = &["href", "imagesrcset"];
+
+/// Makes the element safe to inject with untrusted values, none if it can't be.
+///
+/// Only `<title>`, `<meta>`, `<link>` and `<style>` are kept, with the attributes known to be
+/// harmless for them. Urls with another scheme than `http` or `https` are dropped, as is a
+/// `<base>`, which would redirect every relative url, and a `<meta http-equiv>` which could
+/// navigate away, e.g. `refresh`. Styles can't close their element anymore and any other
+/// content is escaped.
+pub(crate) fn sanitize(mut element_map: ElementMap<'_>) -> Option<ElementMap<'_>> {
+    let tag = element_map.tag.to_ascii_lowercase();
+
+    let Some(attributes) = allowed_attributes(&tag) else {
+        log::warn!(
+            "dioxus-helmet: dropped a <{}> of a sanitized Helmet",
+            element_map.tag
+        );
+        return None;
+    };
+
+    if let Some(http_equiv) = element_map.attribute("http-equiv") {
+        if !HTTP_EQUIVS.contains(&http_equiv.to_ascii_lowercase().as_str()) {
+            log::warn!(
+                "dioxus-helmet: dropped a <meta http-equiv=\"{http_equiv}\"> of a sanitized Helmet"
+            );
+            return None;
+        }
+    }
+
+    element_map.attributes.retain(|(name, value)| {
+        let name = name.to_ascii_lowercase();
+
+        let allowed = name.starts_with("data-")
+            || GLOBAL_ATTRIBUTES.contains(&name.as_str())
+            || attributes.contains(&name.as_str());
+
+        // every candidate of a srcset is a url of its own
+        allowed && (!URL_ATTRIBUTES.contains(&name.as_str()) || value.split(',').all(is_safe_url))
+    });
+
+    element_map.inner_html = element_map.inner_html.map(|inner_html| {
+        if tag == "style" {
+            // `\/` is still a `/` to css, but doesn't end the style element
+            Cow::Owned(inner_html.replace("</", "<\\/"))
+        } else {
+            Cow::Owned(escape(&inner_html))
+        }
+    });
+
+    Some(element_map)
+}
+
+/// The attributes allowed on the tag, none if the tag isn't allowed at all.
+fn allowed_attributes(tag: &str) -> Option<&'static [&'static str]> {
+    match tag {
+        "title" => Some(&[]),
+        "meta" => Some(&[
+            "name",
+            "content",
+            "property",
+            "charset",
+            "http-equiv",
+            "media",
+            "itemprop",
+        ]),
+        "link" => Some(&[
+            "rel",
+            "href",
+            "hreflang",
+            "type",
+            "media",
+            "sizes",
+            "as",
+            "crossorigin",
+            "integrity",
+            "referrerpolicy",
+            "fetchpriority",
+            "blocking",
+            "imagesrcset",
+            "imagesizes",
+            "title",
+            "color",
+        ]),
+        "style" => Some(&["media", "title", "blocking"]),
+        _ => None,
+    }
+}
+
+/// Whether the url is relative or points to `http` or `https`.
+///
+/// Browsers ignore whitespace and control characters within a scheme, e.g. `java\tscript:`,
+/// so they are left out before looking at it.
+fn is_safe_url(url: &str) -> bool {
+    let url = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    // a relative url has no scheme before its path, query or fragment
+    match url
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .split_once(':')
+    {
+        Some((scheme, _)) => scheme == "http" || scheme == "https",
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OwnedElementMap;
+
+    #[test]
+    fn modern_resource_hints_survive() {
+        let element = OwnedElementMap::new("link")
+            .attribute("rel", "preload")
+            .attribute("href", "/hero.avif")
+            .attribute("as", "image")
+            .attribute("fetchpriority", "high")
+            .attribute("blocking", "render");
+
+        let sanitized = sanitize(element.as_element_map()).expect("a link is allowed");
+
+        assert_eq!(sanitized.attribute("fetchpriority"), Some("high"));
+        assert_eq!(sanitized.attribute("blocking"), Some("render"));
+    }
+
+    #[test]
+    fn tags_outside_of_the_allowlist_are_dropped() {
+        ["base", "script", "iframe"].into_iter().for_each(|tag| {
+            let element = OwnedElementMap::new(tag).attribute("href", "/");
+            assert!(sanitize(element.as_element_map()).is_none(), "<{tag}>");
+        });
+    }
+
+    #[test]
+    fn navigating_http_equivs_are_dropped() {
+        let refresh = OwnedElementMap::new("meta")
+            .attribute("http-equiv", "Refresh")
+            .attribute("content", "0; url=https://example.com");
+        let content_type = OwnedElementMap::new("meta")
+            .attribute("http-equiv", "content-type")
+            .attribute("content", "text/html");
+
+        assert!(sanitize(refresh.as_element_map()).is_none());
+        assert!(sanitize(content_type.as_element_map()).is_some());
+    }
+
+    #[test]
+    fn unknown_attributes_and_unsafe_urls_are_dropped() {
+        let element = OwnedElementMap::new("link")
+            .attribute("rel", "icon")
+            .attribute("href", " Java\tScript:alert(1)")
+            .attribute("onload", "alert(1)")
+            .attribute("data-theme", "dark");
+
+        let sanitized = sanitize(element.as_element_map()).expect("a link is allowed");
+
+        assert_eq!(sanitized.attribute("href"), None);
+        assert_eq!(sanitized.attribute("onload"), None);
+        assert_eq!(sanitized.attribute("data-theme"), Some("dark"));
+    }
+
+    #[test]
+    fn urls_are_checked_by_their_scheme() {
+        assert!(is_safe_url("https://example.com/a.css"));
+        assert!(is_safe_url("/a.css?v=1#x"));
+        assert!(is_safe_url("a.css"));
+        assert!(!is_safe_url("data:text/css,body{}"));
+        assert!(!is_safe_url("jav\nascript:alert(1)"));
+    }
+
+    #[test]
+    fn content_is_escaped_and_styles_stay_closed() {
+        let title = OwnedElementMap::new("title").inner_html("<b>Hi</b>");
+        let style = OwnedElementMap::new("style").inner_html("</style><script>");
+
+        let title = sanitize(title.as_element_map()).expect("a title is allowed");
+        let style = sanitize(style.as_element_map()).expect("a style is allowed");
+
+        assert_eq!(title.inner_html.as_deref(), Some("&lt;b&gt;Hi&lt;/b&gt;"));
+        assert_eq!(style.inner_html.as_deref(), Some("<\\/style><script>"));
+    }
+}
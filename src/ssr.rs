@@ -0,0 +1,220 @@
+use crate::{ElementMap, OwnedElementMap, TitleMode, VERSION};
+use std::{cell::RefCell, fmt::Write, rc::Rc};
+
+/// Collects the head elements of every rendered [`Helmet`](crate::Helmet) on the server.
+///
+/// Provide it as context at the root of your app, then call [`HeadCollector::flush_head`]
+/// before streaming the body:
+///
+/// ```rust
+/// fn App(cx: Scope) -> Element {
+///     cx.provide_context(HeadCollector::new());
+///     // ...
+/// }
+/// ```
+///
+/// While a collector is present, Helmet doesn't touch the DOM, unless its `mode` is [`RenderMode::Dom`].
+#[derive(Debug, Clone, Default)]
+pub struct HeadCollector {
+    inner: Rc<RefCell<Collected>>,
+}
+
+/// Where a Helmet places its elements, see the `mode` prop.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RenderMode {
+    /// Collect them if a [`HeadCollector`] was provided, otherwise place them in the DOM.
+    #[default]
+    Auto,
+    /// Place them in the DOM, even if a [`HeadCollector`] was provided.
+    Dom,
+    /// Collect them in the provided [`HeadCollector`], never touching the DOM.
+    SsrCollect,
+}
+
+#[derive(Debug, Default)]
+struct Collected {
+    title: Option<String>,
+    elements: Vec<(u64, OwnedElementMap)>,
+    flushed: Vec<u64>,
+}
+
+impl HeadCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serializes every element registered since the last flush.
+    ///
+    /// Elements which were already flushed won't be emitted again.
+    pub fn flush_head(&self) -> String {
+        let mut collected = self.inner.borrow_mut();
+        let mut html = String::new();
+
+        // the charset has to come before the title
+        let (charsets, elements): (Vec<_>, Vec<_>) = std::mem::take(&mut collected.elements)
+            .into_iter()
+            .partition(|(_, element)| element.as_element_map().is_charset());
+
+        charsets.into_iter().for_each(|(hash, element)| {
+            html.push_str(&element.to_html());
+            collected.flushed.push(hash);
+        });
+
+        if let Some(title) = collected.title.take() {
+            let _ = write!(html, "<title>{}</title>", escape(&title));
+        }
+
+        elements.into_iter().for_each(|(hash, element)| {
+            html.push_str(&element.to_html());
+            collected.flushed.push(hash);
+        });
+
+        html
+    }
+
+    pub(crate) fn set_title(&self, title: &str, mode: TitleMode) {
+        let mut collected = self.inner.borrow_mut();
+
+        if let Some(title) = mode.resolve(collected.title.as_deref(), title) {
+            collected.title = Some(title);
+        }
+    }
+
+    pub(crate) fn register(&self, hash: u64, element_map: &ElementMap) {
+        let mut collected = self.inner.borrow_mut();
+
+        if collected.flushed.contains(&hash) || collected.elements.iter().any(|(h, _)| *h == hash) {
+            return;
+        }
+
+        if let Some(key) = element_map.singleton_key() {
+            collected.elements.retain(|(_, element)| {
+                element.as_element_map().singleton_key().as_ref() != Some(&key)
+            });
+        }
+
+        collected.elements.push((hash, element_map.into()));
+    }
+}
+
+impl OwnedElementMap {
+    fn to_html(&self) -> String {
+        let element_map = self.as_element_map();
+
+        // the hash of `data-helmet-id` differs between targets, e.g. a 64 bit server and a wasm32 client,
+        // so the client recognizes the element by its content instead
+        let mut element = self
+            .clone()
+            .attribute("data-helmet-hash", element_map.content_hash())
+            .attribute("data-helmet-version", VERSION);
+
+        // an explicit key is among the attributes already
+        if let Some(key) = element_map
+            .singleton_key()
+            .filter(|_| element_map.attribute("data-helmet-key").is_none())
+        {
+            element = element.attribute("data-helmet-key", key);
+        }
+
+        element_map_to_html(&element)
+    }
+}
+
+/// Serializes the element as html, e.g. `<meta name="description" content="Helmet" />`.
+///
+/// Attribute values and the text of a title are escaped, any other content is kept as it is.
+pub fn element_map_to_html(element: &OwnedElementMap) -> String {
+    let mut html = format!("<{}", element.tag);
+
+    element.attributes.iter().for_each(|(name, value)| {
+        let _ = write!(html, r#" {name}="{}""#, escape(value));
+    });
+
+    if is_void(&element.tag) {
+        html.push_str(" />");
+        return html;
+    }
+
+    html.push('>');
+
+    if let Some(inner_html) = element.inner_html.as_deref() {
+        // the text of a title is never parsed as html
+        if element.tag == "title" {
+            html.push_str(&escape(inner_html));
+        } else {
+            html.push_str(inner_html);
+        }
+    }
+
+    let _ = write!(html, "</{}>", element.tag);
+
+    html
+}
+
+pub(crate) fn is_void(tag: &str) -> bool {
+    matches!(
+        tag,
+        "area"
+            | "base"
+            | "br"
+            | "col"
+            | "embed"
+            | "hr"
+            | "img"
+            | "input"
+            | "link"
+            | "meta"
+            | "source"
+            | "track"
+            | "wbr"
+    )
+}
+
+pub(crate) fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modern_resource_hints_are_serialized_unchanged() {
+        let element = OwnedElementMap::new("link")
+            .attribute("rel", "preload")
+            .attribute("href", "/font.woff2")
+            .attribute("fetchpriority", "low")
+            .attribute("blocking", "render");
+
+        assert_eq!(
+            element_map_to_html(&element),
+            r#"<link rel="preload" href="/font.woff2" fetchpriority="low" blocking="render" />"#
+        );
+    }
+
+    #[test]
+    fn unicode_is_escaped_without_mojibake() {
+        assert_eq!(escape("猫 & 犬 <🐶>"), "猫 &amp; 犬 &lt;🐶&gt;");
+
+        let element = OwnedElementMap::new("meta")
+            .attribute("name", "description")
+            .attribute("content", "日本語の説明 ✨");
+
+        assert_eq!(
+            element_map_to_html(&element),
+            r#"<meta name="description" content="日本語の説明 ✨" />"#
+        );
+    }
+
+    #[test]
+    fn unicode_titles_are_flushed_whole() {
+        let collector = HeadCollector::new();
+        collector.set_title("受信箱 (3) 📬", TitleMode::Overwrite);
+
+        assert_eq!(collector.flush_head(), "<title>受信箱 (3) 📬</title>");
+    }
+}
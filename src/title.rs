@@ -0,0 +1,171 @@
+use std::cell::RefCell;
+
+/// How the `title` prop of a Helmet treats a title which is already set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TitleMode {
+    /// Replace the current title.
+    #[default]
+    Overwrite,
+    /// Only set the title if there is none yet, e.g. for the default title of a layout.
+    SetIfEmpty,
+    /// Append to the current title, separated by ` | `.
+    Append,
+    /// Append to the title of the enclosing Helmets with the separator, e.g. `App > Settings`
+    /// with `Breadcrumb(" > ")`, which reverts to `App` once this Helmet is dropped.
+    Breadcrumb(&'static str),
+}
+
+impl TitleMode {
+    /// The title to set in place of the current one, none to keep it.
+    pub(crate) fn resolve(self, current: Option<&str>, title: &str) -> Option<String> {
+        let current = current.filter(|current| !current.trim().is_empty());
+
+        match (self, current) {
+            (TitleMode::Overwrite, _) | (_, None) => Some(title.to_owned()),
+            (TitleMode::SetIfEmpty, Some(_)) => None,
+            (TitleMode::Append, Some(current)) => append(current, " | ", title),
+            (TitleMode::Breadcrumb(separator), Some(current)) => append(current, separator, title),
+        }
+    }
+}
+
+fn append(current: &str, separator: &str, title: &str) -> Option<String> {
+    // already appended by a previous render
+    if current == title || current.ends_with(&format!("{separator}{title}")) {
+        None
+    } else {
+        Some(format!("{current}{separator}{title}"))
+    }
+}
+
+thread_local! {
+    static BREADCRUMBS: RefCell<Breadcrumbs> = RefCell::new(Breadcrumbs::default());
+}
+
+/// The segments of the mounted Helmets with [`TitleMode::Breadcrumb`], in the order they mounted.
+#[derive(Default)]
+struct Breadcrumbs {
+    /// The title before the first segment.
+    base: Option<String>,
+    segments: Vec<Segment>,
+}
+
+struct Segment {
+    scope: usize,
+    seed: i64,
+    separator: &'static str,
+    text: String,
+}
+
+impl Breadcrumbs {
+    fn title(&self) -> String {
+        self.segments
+            .iter()
+            .fold(self.base.clone().unwrap_or_default(), |title, segment| {
+                if title.trim().is_empty() {
+                    segment.text.clone()
+                } else {
+                    format!("{title}{}{}", segment.separator, segment.text)
+                }
+            })
+    }
+
+    /// Removes the matching segments, returns the title without them, none if none matched.
+    fn remove(&mut self, matches: impl Fn(&Segment) -> bool) -> Option<String> {
+        let count = self.segments.len();
+        self.segments.retain(|segment| !matches(segment));
+
+        if self.segments.len() == count {
+            return None;
+        }
+
+        let title = self.title();
+
+        if self.segments.is_empty() {
+            self.base = None;
+        }
+
+        Some(title)
+    }
+}
+
+/// Sets the segment of the Helmet in the scope, keeping its place, returns the combined title.
+pub(crate) fn push_breadcrumb(
+    scope: usize,
+    seed: i64,
+    separator: &'static str,
+    text: &str,
+    current: Option<String>,
+) -> String {
+    BREADCRUMBS.with(|breadcrumbs| {
+        let mut breadcrumbs = breadcrumbs.borrow_mut();
+
+        if breadcrumbs.segments.is_empty() {
+            breadcrumbs.base = current;
+        }
+
+        let segment = Segment {
+            scope,
+            seed,
+            separator,
+            text: text.to_owned(),
+        };
+
+        match breadcrumbs
+            .segments
+            .iter_mut()
+            .find(|segment| segment.scope == scope)
+        {
+            Some(existing) => *existing = segment,
+            None => breadcrumbs.segments.push(segment),
+        }
+
+        breadcrumbs.title()
+    })
+}
+
+/// Removes the segment of the Helmet in the scope, returns the title without it, none if it had no segment.
+pub(crate) fn pop_breadcrumb(scope: usize) -> Option<String> {
+    BREADCRUMBS.with(|breadcrumbs| {
+        breadcrumbs
+            .borrow_mut()
+            .remove(|segment| segment.scope == scope)
+    })
+}
+
+/// Removes the segments of the Helmets with the seed, returns the title without them,
+/// none if they had no segment.
+pub(crate) fn pop_breadcrumbs_of(seed: i64) -> Option<String> {
+    BREADCRUMBS.with(|breadcrumbs| {
+        breadcrumbs
+            .borrow_mut()
+            .remove(|segment| segment.seed == seed)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unicode_titles_are_appended_whole() {
+        assert_eq!(
+            TitleMode::Append
+                .resolve(Some("東京 🗼"), "設定 ⚙️")
+                .as_deref(),
+            Some("東京 🗼 | 設定 ⚙️")
+        );
+        assert_eq!(
+            TitleMode::Breadcrumb(" › ")
+                .resolve(Some("😀"), "受信箱")
+                .as_deref(),
+            Some("😀 › 受信箱")
+        );
+    }
+
+    #[test]
+    fn unicode_titles_already_appended_are_kept() {
+        assert_eq!(TitleMode::Append.resolve(Some("東京 | 設定"), "設定"), None);
+        assert_eq!(TitleMode::SetIfEmpty.resolve(Some("🗼"), "設定"), None);
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{inject_owned, OwnedElementMap, Placement};
+use std::{collections::HashMap, fmt::Write};
+
+/// Injects design tokens as css custom properties of `:root`.
+///
+/// ```rust
+/// let tokens = HashMap::from([("primary", "#0af"), ("--radius", "4px")]);
+/// inject_tokens(&tokens);
+/// ```
+///
+/// The `--` prefix is optional. Calling it again updates the content of the same style element.
+pub fn inject_tokens<K, V>(tokens: &HashMap<K, V>)
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    // sorted, so the same tokens always produce the same css
+    let mut tokens = tokens
+        .iter()
+        .map(|(name, value)| (name.as_ref(), value.as_ref()))
+        .collect::<Vec<_>>();
+    tokens.sort_unstable();
+
+    let mut css = String::from(":root {");
+
+    tokens.iter().for_each(|(name, value)| {
+        let name = name.trim_start_matches("--");
+        let _ = write!(css, " --{name}: {value};");
+    });
+
+    css.push_str(" }");
+
+    let element = OwnedElementMap::new("style")
+        .attribute("data-helmet-key", "helmet-tokens")
+        .inner_html(css);
+
+    inject_owned(
+        0,
+        &element,
+        Placement {
+            merge_attributes: true,
+            ..Placement::default()
+        },
+    );
+}
@@ -0,0 +1,152 @@
+use crate::{lock_init_cache, remove_elements, ElementMap, PendingInsertion, Placement};
+use std::cell::RefCell;
+
+thread_local! {
+    static PENDING: RefCell<Option<Batch>> = const { RefCell::new(None) };
+}
+
+#[derive(Default)]
+struct Batch {
+    depth: usize,
+    removals: Vec<(web_sys::Document, u64)>,
+    insertions: Vec<PendingInsertion>,
+}
+
+/// Batches every head change until the transaction is committed or dropped,
+/// so swapping the head on a route change doesn't flash missing elements.
+///
+/// ```rust
+/// let transaction = dioxus_helmet::transaction();
+/// // navigate, render...
+/// transaction.commit();
+/// ```
+///
+/// Elements which are removed and injected again within one transaction stay untouched.
+#[must_use = "the changes are applied as soon as the transaction is dropped"]
+pub struct Transaction {
+    _private: (),
+}
+
+/// Starts a [`Transaction`]. Nested transactions are applied together with the outermost one.
+pub fn transaction() -> Transaction {
+    PENDING.with(|pending| {
+        pending
+            .borrow_mut()
+            .get_or_insert_with(Batch::default)
+            .depth += 1;
+    });
+
+    Transaction { _private: () }
+}
+
+impl Transaction {
+    /// Applies the batched changes, the same as dropping the transaction.
+    pub fn commit(self) {}
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        let batch = PENDING.with(|pending| {
+            let mut pending = pending.borrow_mut();
+            let batch = pending.as_mut()?;
+            batch.depth -= 1;
+
+            if batch.depth == 0 {
+                pending.take()
+            } else {
+                None
+            }
+        });
+
+        if let Some(batch) = batch {
+            apply(batch);
+        }
+    }
+}
+
+/// Applies the changes batched so far by the running transaction, which keeps running.
+pub(crate) fn flush() {
+    let batch = PENDING.with(|pending| {
+        let mut pending = pending.borrow_mut();
+        let batch = pending.as_mut()?;
+
+        Some(Batch {
+            depth: batch.depth,
+            removals: std::mem::take(&mut batch.removals),
+            insertions: std::mem::take(&mut batch.insertions),
+        })
+    });
+
+    if let Some(batch) = batch {
+        apply(batch);
+    }
+}
+
+fn apply(batch: Batch) {
+    let Some(mut init_cache) = lock_init_cache() else {
+        return;
+    };
+
+    let Batch {
+        mut removals,
+        insertions,
+        ..
+    } = batch;
+
+    let insertions = insertions
+        .into_iter()
+        .filter(|insertion| {
+            if let Some(index) = removals
+                .iter()
+                .position(|(_, removal)| *removal == insertion.hash)
+            {
+                removals.remove(index);
+                false
+            } else {
+                true
+            }
+        })
+        .collect::<Vec<_>>();
+
+    removals
+        .iter()
+        .for_each(|(document, hash)| remove_elements(document, *hash));
+
+    insertions
+        .iter()
+        .for_each(|insertion| insertion.apply(&mut init_cache));
+}
+
+/// Queues the insertion if a transaction is running.
+pub(crate) fn defer_insertion(
+    document: &web_sys::Document,
+    parent: &web_sys::Element,
+    hash: u64,
+    element_map: &ElementMap,
+    placement: Placement,
+) -> bool {
+    PENDING.with(|pending| match pending.borrow_mut().as_mut() {
+        Some(batch) => {
+            batch.insertions.push(PendingInsertion::new(
+                document,
+                parent,
+                hash,
+                element_map,
+                placement,
+            ));
+            true
+        }
+        None => false,
+    })
+}
+
+/// Queues the removal if a transaction is running.
+pub(crate) fn defer_removal(document: &web_sys::Document, hash: u64) -> bool {
+    PENDING.with(|pending| match pending.borrow_mut().as_mut() {
+        Some(batch) => {
+            batch.removals.push((document.clone(), hash));
+            true
+        }
+        None => false,
+    })
+}
@@ -0,0 +1,50 @@
+use crate::ElementMap;
+
+/// Titles longer than this are most likely a mistake, browsers cut them off way earlier.
+const MAX_TITLE_LENGTH: usize = 1024;
+
+/// Warns about props which would silently fail to inject, only called in debug builds.
+pub(crate) fn validate(title: Option<&str>, element_maps: &[ElementMap]) {
+    if let Some(title) = title.filter(|title| title.len() > MAX_TITLE_LENGTH) {
+        log::warn!(
+            "dioxus-helmet: the title is {} bytes long, did you pass the wrong value?",
+            title.len()
+        );
+    }
+
+    element_maps.iter().for_each(|element_map| {
+        if !is_valid_tag(element_map.tag) {
+            log::warn!(
+                "dioxus-helmet: <{}> is not a valid tag name, it won't be injected",
+                element_map.tag
+            );
+        }
+
+        element_map
+            .attributes
+            .iter()
+            .filter(|(name, _)| !is_valid_attribute(name))
+            .for_each(|(name, _)| {
+                log::warn!(
+                    "dioxus-helmet: `{name}` of <{}> is not a valid attribute name, it won't be set",
+                    element_map.tag
+                );
+            });
+    });
+}
+
+fn is_valid_tag(tag: &str) -> bool {
+    let mut chars = tag.chars();
+
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+fn is_valid_attribute(name: &str) -> bool {
+    !name.is_empty()
+        && !name.chars().any(|c| {
+            c.is_whitespace() || c.is_control() || matches!(c, '"' | '\'' | '>' | '/' | '=')
+        })
+}
@@ -0,0 +1,68 @@
+use crate::{element_hash, ElementMap, OwnedElementMap};
+use lazy_static::lazy_static;
+use std::sync::Mutex;
+use wasm_bindgen::JsCast;
+
+lazy_static! {
+    static ref WORKER_SINK: Mutex<Option<fn(WorkerMessage)>> = Mutex::new(None);
+}
+
+/// What a Helmet without access to a document would have done to the head.
+#[derive(Debug, Clone)]
+pub enum WorkerMessage {
+    Title(String),
+    Inject { hash: u64, element: OwnedElementMap },
+    Remove { hash: u64 },
+}
+
+/// Whether the code runs inside a Web Worker, where there is no document to place elements in.
+pub fn is_worker() -> bool {
+    cfg!(target_arch = "wasm32") && js_sys::global().is_instance_of::<web_sys::WorkerGlobalScope>()
+}
+
+/// Sets the function which receives the head changes of Helmets rendered inside a Web Worker,
+/// e.g. to `postMessage` them to the main thread.
+///
+/// Without a sink, Helmet does nothing in a worker.
+pub fn set_worker_sink(sink: fn(WorkerMessage)) {
+    let mut worker_sink = WORKER_SINK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *worker_sink = Some(sink);
+}
+
+fn worker_sink() -> Option<fn(WorkerMessage)> {
+    *WORKER_SINK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub(crate) fn send_injections(seed: i64, title: Option<&str>, element_maps: &[ElementMap]) {
+    let Some(sink) = worker_sink().filter(|_| is_worker()) else {
+        return;
+    };
+
+    if let Some(title) = title {
+        sink(WorkerMessage::Title(title.to_owned()));
+    }
+
+    element_maps.iter().for_each(|element_map| {
+        sink(WorkerMessage::Inject {
+            hash: element_hash(seed, element_map),
+            element: element_map.into(),
+        })
+    });
+}
+
+#[cfg(not(feature = "ssr-only"))]
+pub(crate) fn send_removals(seed: i64, element_maps: &[ElementMap]) {
+    let Some(sink) = worker_sink().filter(|_| is_worker()) else {
+        return;
+    };
+
+    element_maps.iter().for_each(|element_map| {
+        sink(WorkerMessage::Remove {
+            hash: element_hash(seed, element_map),
+        })
+    });
+}
@@ -0,0 +1,19 @@
+#[test]
+fn meta_refuses_incomplete_arguments() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/meta_*.rs");
+}
+
+#[test]
+fn meta_accepts_either_order() {
+    use dioxus_helmet::meta;
+
+    assert_eq!(
+        meta!(content = "Helmet", name = "description"),
+        meta!(name = "description", content = "Helmet")
+    );
+    assert_eq!(
+        meta!(content = "Helmet", property = "og:title"),
+        meta!(property = "og:title", content = "Helmet")
+    );
+}
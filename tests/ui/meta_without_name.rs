@@ -0,0 +1,5 @@
+use dioxus_helmet::meta;
+
+fn main() {
+    let _ = meta!(content = "Helmet");
+}